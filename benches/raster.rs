@@ -0,0 +1,59 @@
+//! Software rasterization of a large board, to compare the build with the
+//! `parallel` feature against the one without:
+//!
+//! ```sh
+//! cargo bench --bench raster
+//! cargo bench --bench raster --features parallel
+//! ```
+
+use cato_display_app::{
+    app,
+    segments::{
+        raster,
+        segmented_font::DEFAULT,
+        svg::{BoardCell, SvgLayout},
+        DigitOptions,
+    },
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use iced::{Color, Size};
+
+const ROWS: usize = 4;
+const COLS: usize = 24;
+const SCALE: f32 = 4.;
+const TEXT: &str = "The quick brown fox jumps over the lazy dog 0123456789";
+
+fn bench_rasterize(c: &mut Criterion) {
+    let mut chars = TEXT.chars().cycle();
+    let board: Vec<Vec<BoardCell>> = (0..ROWS)
+        .map(|_| {
+            (0..COLS)
+                .map(|_| DEFAULT.glyph(&chars.next().unwrap()).into())
+                .collect()
+        })
+        .collect();
+
+    let mut options = DigitOptions::default();
+    let size = options.size;
+    options.resize(Size::new(size.width * SCALE, size.height * SCALE));
+    options.thickness *= SCALE;
+    options.gap *= SCALE;
+    let layout = SvgLayout {
+        char_spacing: app::CHAR_SPACING * SCALE,
+        line_spacing: app::LINE_SPACING * SCALE,
+        padding: app::BOARD_PADDING * SCALE,
+        background: Color::BLACK,
+    };
+
+    let name = if cfg!(feature = "parallel") {
+        "rasterize 4x24 parallel"
+    } else {
+        "rasterize 4x24"
+    };
+    c.bench_function(name, |b| {
+        b.iter(|| raster::rasterize(&board, &options, &layout))
+    });
+}
+
+criterion_group!(benches, bench_rasterize);
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+//! Redraw of a whole board with the software renderer, with the geometry of
+//! the glyphs already cached, cached from scratch and not cached at all.
+//! Each is measured with one path per segment and with batched paths.
+
+use cato_display_app::{
+    app::Message,
+    segments::{segmented_font::DEFAULT, DigitDisplay, DigitOptions},
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use iced::{
+    advanced::{layout, renderer, widget::Tree, Layout},
+    mouse,
+    widget::{self as w, canvas::Style},
+    Color, Font, Pixels, Rectangle, Size,
+};
+
+const ROWS: usize = 4;
+const COLS: usize = 24;
+const TEXT: &str = "The quick brown fox jumps over the lazy dog 0123456789";
+
+fn renderer() -> iced::Renderer {
+    iced::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+        iced_tiny_skia::Backend::new(),
+        Font::DEFAULT,
+        Pixels(16.),
+    ))
+}
+
+/// Board of digits showing [`TEXT`], with the lit segments drawn in
+/// `on_fill` if it is set, which bypasses the caches.
+fn board(
+    display: &DigitDisplay,
+    on_fill: Option<Style>,
+) -> iced::Element<'_, Message> {
+    let mut chars = TEXT.chars().cycle();
+    let rows = (0..ROWS).map(|_| {
+        let digits = (0..COLS).map(|_| {
+            let glyph = DEFAULT.glyph(&chars.next().unwrap());
+            display.instantiate_with_fill(glyph, on_fill.clone())
+        });
+        w::row(digits).spacing(8.).into()
+    });
+    w::column(rows).spacing(16.).into()
+}
+
+/// Lays out and draws the board, then drops the recorded primitives.
+fn redraw(
+    renderer: &mut iced::Renderer,
+    display: &DigitDisplay,
+    on_fill: Option<Style>,
+) {
+    let board = board(display, on_fill);
+    let mut tree = Tree::new(&board);
+    let node = board.as_widget().layout(
+        &mut tree,
+        renderer,
+        &layout::Limits::new(Size::ZERO, Size::INFINITY),
+    );
+    board.as_widget().draw(
+        &tree,
+        renderer,
+        &iced::Theme::Dark,
+        &renderer::Style {
+            text_color: Color::WHITE,
+        },
+        Layout::new(&node),
+        mouse::Cursor::Unavailable,
+        &Rectangle::with_size(node.size()),
+    );
+    if let iced::Renderer::TinySkia(renderer) = renderer {
+        renderer.start_layer();
+    }
+}
+
+fn bench_redraw(c: &mut Criterion) {
+    let mut renderer = renderer();
+    let mut group = c.benchmark_group("redraw");
+
+    for (name, batched) in [("per segment", false), ("batched", true)] {
+        let options = DigitOptions {
+            batched,
+            ..Default::default()
+        };
+
+        let display = DigitDisplay::new(options.clone());
+        redraw(&mut renderer, &display, None);
+        group.bench_function(format!("cached/{name}"), |b| {
+            b.iter(|| redraw(&mut renderer, &display, None))
+        });
+
+        group.bench_function(format!("cold cache/{name}"), |b| {
+            b.iter_batched(
+                || DigitDisplay::new(options.clone()),
+                |display| redraw(&mut renderer, &display, None),
+                BatchSize::SmallInput,
+            )
+        });
+
+        let on_fill = Style::Solid(Color::WHITE);
+        group.bench_function(format!("uncached/{name}"), |b| {
+            b.iter(|| redraw(&mut renderer, &display, Some(on_fill.clone())))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_redraw);
+criterion_main!(benches);
@@ -1,8 +1,26 @@
-use std::iter::repeat;
+use std::{
+    collections::HashMap,
+    io::Write,
+    iter::{repeat, repeat_n},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use iced::{Application, Color, Length};
+use iced::{
+    widget::{
+        canvas::{Gradient, Style},
+        scrollable,
+    },
+    Application, Color, Length, Size,
+};
 
-use crate::segments::{self, DigitOptions};
+use crate::config::Config;
+use crate::markup::{self, Cell};
+use crate::profiles::{self, Profile, Profiles};
+use crate::segments::{
+    self, segmented_font::SegmentedFont, DigitOptions, Segment, SegmentBits,
+};
+use crate::timer::{Timer, TimerMode};
 
 struct LoadingStatus {
     current: u32,
@@ -33,39 +51,1626 @@ impl LoadingStatus {
 #[derive(Debug, Clone)]
 pub enum Message {
     FontLoaded {
-        name: &'static str,
+        name: String,
         result: Result<(), iced::font::Error>,
     },
+    SetZoom(f32),
+    SetAutoFit(bool),
+    SetMirrorX(bool),
+    SetUpsideDown(bool),
+    SetDigitWidth(f32),
+    SetDigitHeight(f32),
     SetDigitThickness(f32),
     SetDigitGap(f32),
+    SetDigitSlant(f32),
+    SetDigitAspect(f32),
+    SetDigitColor(Color),
+    SetFade(bool),
+    SetBrightness(f32),
+    SetRenderMode(segments::RenderMode),
+    SetOutlineWidth(f32),
+    SetCrisp(bool),
+    SetBatched(bool),
+    SetGlow(bool),
+    SetGlowIntensity(f32),
+    SetGlowSpread(f32),
+    SetTransition(bool),
+    SetTransitionDuration(f32),
+    TransitionTick(Instant),
+    SetFlicker(f32),
+    FlickerTick,
+    ApplyDisplayTheme(DisplayTheme),
+    SelectProfile(String),
+    /// Overwrite the selected profile with the current look
+    SaveProfile,
+    SetProfileName(String),
+    CreateProfile,
+    RenameProfile,
+    DeleteProfile,
+    WindowResized(Size),
+    WindowMoved(iced::Point),
+    CloseRequested,
+    /// Switch between full screen, showing only the board, and a window
+    ToggleFullscreen,
+    ExitFullscreen,
+    /// Show or hide everything but the board
+    ToggleControls,
+    SetTheme(iced::Theme),
+    SetBackground(Color),
+    SetCharSpacing(f32),
+    SetLineSpacing(f32),
+    SetRows(usize),
+    SetCols(usize),
     TextAreaAction(iced::widget::text_editor::Action),
-    Scrolled(iced::widget::scrollable::Viewport),
+    ClearText,
+    ExportSvg,
+    CopyAscii,
+    SetGifFrames(usize),
+    SetGifFps(f32),
+    RecordGif,
+    SetBlink(bool),
+    SetBlinkRate(f32),
+    Tick,
+    SetRtl(bool),
+    SetMergeDecimalPoints(bool),
+    SetTabWidth(usize),
+    SetWordWrap(bool),
+    SetMarkClipped(bool),
+    SetShowControlChars(bool),
+    SetMarquee(bool),
+    SetMarqueeSpeed(f32),
+    SetMarqueeGap(usize),
+    MarqueeTick,
+    SetTypewriter(bool),
+    SetTypewriterSpeed(f32),
+    SetTypewriterLoop(bool),
+    TypewriterTick,
+    SelectFont(usize),
+    /// Sets the font of a row by its index, or `None` for the active font
+    SetRowFont(usize, Option<String>),
+    /// Sets the color of a row by its index, or `None` for the display color
+    SetRowColor(usize, Option<Color>),
+    SetDisplayKind(segments::DisplayKind),
+    SetFallbackGlyph(FallbackGlyph),
+    /// Sets the font providing the glyphs missing from the active font by
+    /// its name, or `None` to leave them blank
+    SetFallbackFont(Option<String>),
+    SetEditor(bool),
+    SetEditorChar(String),
+    EditGlyph(SegmentBits),
+    SetDamageEditor(bool),
+    SetRawSegments(String),
+    SetDeadSegments(SegmentBits),
+    SetStuckSegments(SegmentBits),
+    UndoGlyphEdit,
+    RedoGlyphEdit,
+    /// Writes the active font to [`SEGMENTED_FONTS_DIR`]
+    SaveFont,
+    Scrolled(scrollable::Viewport),
+    SetSerialPath(String),
+    SetSerialOutput(bool),
+    SerialTick,
+    SetRemote(bool),
+    RemoteText(String),
+    WatchedFile(Result<String, String>),
+    SetClock(bool),
+    SetClockSeconds(bool),
+    SetClockColons(bool),
+    ClockTick,
+    SetTimer(bool),
+    SetTimerMode(TimerMode),
+    SetTimerDuration(Duration),
+    StartTimer,
+    PauseTimer,
+    ResetTimer,
+    TimerTick(Instant),
+    /// The countdown has reached zero
+    TimerFinished,
+    SetTestPattern(TestPattern),
+    TestTick,
+}
+
+/// Default spacing between the digits
+pub const CHAR_SPACING: f32 = 8.;
+/// Default spacing between the rows
+pub const LINE_SPACING: f32 = 16.;
+pub const BOARD_PADDING: f32 = 8.;
+/// Space around the contents of the window
+const WINDOW_PADDING: f32 = 16.;
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.25..=4.;
+/// Room left beside a board fit to the window for the scrollbar
+const FIT_MARGIN: f32 = 16.;
+const SVG_EXPORT_PATH: &str = "display.svg";
+const GIF_EXPORT_PATH: &str = "display.gif";
+const SEGMENTED_FONTS_DIR: &str = "segmented_fonts";
+const DISPLAY_SCROLLABLE: &str = "display";
+const TEST_CYCLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Rate at which changes are streamed to the serial port
+const SERIAL_FRAME_RATE: f32 = 20.;
+/// Initial duration of the countdown
+const TIMER_DURATION: Duration = Duration::from_secs(5 * 60);
+/// Interval at which a running timer is updated, short enough for the
+/// seconds to change on time
+const TIMER_TICK_INTERVAL: Duration = Duration::from_millis(100);
+/// Choices for the columns between tab stops
+const TAB_WIDTHS: [usize; 4] = [1, 2, 4, 8];
+/// Time for which flickering segments stay dropped or dimmed
+const FLICKER_INTERVAL: Duration = Duration::from_millis(50);
+/// Ticks for which a looping typewriter shows the whole text before
+/// starting over
+const TYPEWRITER_LOOP_PAUSE: usize = 10;
+/// Maximum number of glyph edits that can be undone
+const GLYPH_HISTORY_LEN: usize = 100;
+
+/// Entry of the font selection list.
+#[derive(Debug, Clone, PartialEq)]
+struct FontChoice {
+    index: usize,
+    name: String,
+}
+
+impl std::fmt::Display for FontChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Entry of the font selection list of a row, where `None` follows the
+/// active font.
+#[derive(Debug, Clone, PartialEq)]
+struct RowFontChoice(Option<FontChoice>);
+
+impl std::fmt::Display for RowFontChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(choice) => choice.fmt(f),
+            None => f.write_str("Same as board"),
+        }
+    }
+}
+
+/// Entry of the list of fonts to take missing glyphs from, where `None`
+/// takes them from no font.
+#[derive(Debug, Clone, PartialEq)]
+struct FallbackFontChoice(Option<FontChoice>);
+
+impl std::fmt::Display for FallbackFontChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(choice) => choice.fmt(f),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// Entry of the color selection list of a row, where `None` uses the
+/// display color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RowColorChoice(Option<(&'static str, Color)>);
+
+impl std::fmt::Display for RowColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some((name, _)) => f.write_str(name),
+            None => f.write_str("Display color"),
+        }
+    }
+}
+
+/// Named combination of display colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayTheme {
+    pub name: &'static str,
+    pub on: Color,
+    /// Color the lit segments fade to at the bottom, if any
+    pub fade_to: Option<Color>,
+    pub background: Color,
+}
+
+impl DisplayTheme {
+    fn on_fill(&self, options: &DigitOptions) -> Style {
+        match self.fade_to {
+            Some(bottom) => options.vertical_gradient(self.on, bottom),
+            None => Style::Solid(self.on),
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+pub const DISPLAY_THEMES: [DisplayTheme; 5] = [
+    DisplayTheme {
+        name: "Classic red",
+        on: Color::from_rgb(1., 0., 0.),
+        fade_to: None,
+        background: Color::BLACK,
+    },
+    DisplayTheme {
+        name: "Amber",
+        on: Color::from_rgb(1., 0.69, 0.),
+        fade_to: None,
+        background: Color::from_rgb(0.06, 0.03, 0.),
+    },
+    DisplayTheme {
+        name: "Emerald",
+        on: Color::from_rgb(0.2, 1., 0.5),
+        fade_to: None,
+        background: Color::from_rgb(0., 0.05, 0.02),
+    },
+    DisplayTheme {
+        name: "Ice blue",
+        on: Color::from_rgb(0.55, 0.85, 1.),
+        fade_to: None,
+        background: Color::from_rgb(0.01, 0.03, 0.08),
+    },
+    DisplayTheme {
+        name: "Sunset",
+        on: Color::from_rgb(1., 0.8, 0.2),
+        fade_to: Some(Color::from_rgb(0.9, 0.1, 0.3)),
+        background: Color::from_rgb(0.05, 0., 0.03),
+    },
+];
+
+/// Glyph shown for characters missing from the active font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackGlyph {
+    Blank,
+    AllSegments,
+    /// The glyph of `?`
+    Replacement,
+}
+
+impl FallbackGlyph {
+    const ALL: [Self; 3] = [Self::Blank, Self::AllSegments, Self::Replacement];
+
+    fn of(font: &SegmentedFont) -> Self {
+        match font.fallback_glyph() {
+            None => Self::Blank,
+            Some(bits) if bits == SegmentBits::all() => Self::AllSegments,
+            Some(_) => Self::Replacement,
+        }
+    }
+}
+
+impl std::fmt::Display for FallbackGlyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Blank => "Blank",
+            Self::AllSegments => "All segments",
+            Self::Replacement => "?",
+        })
+    }
+}
+
+/// Diagnostic patterns shown on every digit instead of the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestPattern {
+    #[default]
+    Off,
+    /// Lights one segment at a time, advancing every tick
+    Cycle,
+    AllOn,
+}
+
+impl TestPattern {
+    const ALL: [Self; 3] = [Self::Off, Self::Cycle, Self::AllOn];
+}
+
+impl std::fmt::Display for TestPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "Off",
+            Self::Cycle => "Cycle segments",
+            Self::AllOn => "All on",
+        })
+    }
+}
+
+/// Change of a single glyph in the font editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GlyphEdit {
+    ch: char,
+    /// Segments before the edit, or `None` if the font had no glyph
+    old: Option<SegmentBits>,
+    new: SegmentBits,
+}
+
+/// Cell of the board as it is shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StyledCell {
+    segments: SegmentBits,
+    /// Color set by the markup, if it differs from the display color
+    color: Option<Color>,
+    /// Character the segments show, unless they do not come from the font
+    ch: Option<char>,
+}
+
+impl StyledCell {
+    const BLANK: Self = Self {
+        segments: SegmentBits::new(),
+        color: None,
+        ch: None,
+    };
+}
+
+/// Startup options given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct Flags {
+    /// Initial text of the display
+    pub text: Option<String>,
+    /// Show the lines read from standard input
+    pub stdin: bool,
+    /// Show the contents of this file, reloading it when it changes
+    pub watch: Option<std::path::PathBuf>,
+    pub config: Config,
 }
 
 pub struct CatoDisplayApp {
     loading: LoadingStatus,
     digit_display: segments::DigitDisplay,
     text: iced::widget::text_editor::Content,
+    rows: usize,
+    cols: usize,
+    background: Color,
+    profiles: Profiles,
+    /// Name of the last applied or saved profile
+    profile: Option<String>,
+    /// Name entered for creating or renaming a profile
+    profile_name: String,
+    /// Horizontal spacing between the digits
+    char_spacing: f32,
+    /// Vertical spacing between the rows
+    line_spacing: f32,
+    /// Last known size of the window
+    window_size: [f32; 2],
+    /// Size of the window outside of full screen, restored when leaving full
+    /// screen and saved to the config
+    windowed_size: [f32; 2],
+    /// Position of the window outside of full screen, saved to the config
+    window_position: Option<[f32; 2]>,
+    /// Show only the board, covering the whole screen
+    fullscreen: bool,
+    /// Show the controls above the board
+    show_controls: bool,
+    /// Theme of the controls, the board keeps its own colors
+    theme: iced::Theme,
+    /// Zoom the board to fill the width of the window
+    auto_fit: bool,
+    blink: bool,
+    /// Blinks per second
+    blink_rate: f32,
+    /// Fill the rows from right to left. This only reverses the order of
+    /// the cells and does not implement the bidirectional algorithm.
+    rtl: bool,
+    /// Show a period following a digit as its decimal point
+    merge_decimal_points: bool,
+    /// Columns between the tab stops of the text
+    tab_width: usize,
+    /// Show control characters as a placeholder instead of removing them
+    show_control_chars: bool,
+    /// Continue long lines on the next rows, breaking them between words
+    word_wrap: bool,
+    /// Mark rows and boards on which the text does not fit by an arrow in
+    /// their last cell
+    mark_clipped: bool,
+    marquee: bool,
+    /// Characters per second
+    marquee_speed: f32,
+    marquee_gap: usize,
+    marquee_offset: usize,
+    /// Reveal the text one character at a time
+    typewriter: bool,
+    /// Characters per second
+    typewriter_speed: f32,
+    /// Start over once the whole text is revealed
+    typewriter_loop: bool,
+    /// Number of characters revealed so far
+    typewriter_revealed: usize,
+    /// Available segmented fonts and their names
+    fonts: Vec<(String, SegmentedFont)>,
+    /// Index of the active font
+    font: usize,
+    /// Name of the font of each row, or `None` to use the active font
+    row_fonts: Vec<Option<String>>,
+    /// Name of the font providing the missing glyphs, by the name of the
+    /// font they are missing from
+    fallback_fonts: HashMap<String, String>,
+    /// Color of each row, or `None` to use the display color. Colors from
+    /// the markup take precedence.
+    row_colors: Vec<Option<Color>>,
+    editor: bool,
+    /// Character whose glyph is being edited
+    editor_char: char,
+    editor_display: segments::DigitDisplay,
+    /// Show the editors of the dead and stuck segments
+    damage_editor: bool,
+    /// Segment names or hex value of the raw segment preview
+    raw_segments: String,
+    /// Character at the text cursor, whose glyph is inspected
+    cursor_char: Option<char>,
+    /// Glyph edits of the active font, most recent last
+    undo_stack: Vec<GlyphEdit>,
+    /// Undone glyph edits, most recently undone last
+    redo_stack: Vec<GlyphEdit>,
+    /// Last known scroll position of the display
+    viewport: Option<scrollable::Viewport>,
+    serial_path: String,
+    /// Port the display is streamed to, if enabled
+    serial: Option<Box<dyn serialport::SerialPort>>,
+    /// Last frame written to the serial port
+    serial_frame: Vec<u8>,
+    /// Number of frames of recorded GIFs
+    gif_frames: usize,
+    gif_fps: f32,
+    /// Whether text is accepted over TCP
+    remote: bool,
+    /// Whether text is read from standard input
+    stdin: bool,
+    /// File whose contents are shown
+    watch: Option<std::path::PathBuf>,
+    /// Why the watched file could not be shown
+    watch_error: Option<String>,
+    /// Show the current time instead of the text
+    clock: bool,
+    clock_seconds: bool,
+    /// Show the colons of the clock in narrow cells of their own
+    clock_colons: bool,
+    time: chrono::DateTime<chrono::Local>,
+    /// Show the timer instead of the text
+    show_timer: bool,
+    timer: Timer,
+    /// Time of the last timer tick
+    timer_now: Instant,
+    test_pattern: TestPattern,
+    /// Segment lit by the cycle test pattern
+    test_segment: usize,
+    /// Board at the start of the latest crossfade
+    transition_board: Vec<Vec<SegmentBits>>,
+    /// End of the latest crossfade, while it is still running
+    transition_end: Option<Instant>,
+}
+
+impl CatoDisplayApp {
+    /// Segments of every cell on the board, padded with blanks to fill all
+    /// rows and columns.
+    fn board(&self) -> Vec<Vec<SegmentBits>> {
+        self.styled_board()
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.segments).collect())
+            .collect()
+    }
+
+    /// Like [`Self::board`], but with the segments which are actually lit,
+    /// as exported and sent to the hardware.
+    fn shown_board(&self) -> Vec<Vec<SegmentBits>> {
+        self.board()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|bits| self.digit_display.shown(bits))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Like [`Self::shown_board`], but with the color of every cell, as
+    /// drawn into exported files.
+    fn export_board(&self) -> Vec<Vec<segments::svg::BoardCell>> {
+        self.styled_board()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| segments::svg::BoardCell {
+                        segments: self.digit_display.shown(cell.segments),
+                        color: cell.color,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Like [`Self::board`], but with the color of every cell set by the
+    /// markup in the text and the characters shown.
+    fn styled_board(&self) -> Vec<Vec<StyledCell>> {
+        let test_bits = match self.test_pattern {
+            TestPattern::Off => None,
+            TestPattern::Cycle => {
+                Some(SegmentBits::from(1 << self.test_segment))
+            }
+            TestPattern::AllOn => Some(SegmentBits::all()),
+        };
+        if let Some(bits) = test_bits {
+            let cell = StyledCell {
+                segments: bits,
+                ..StyledCell::BLANK
+            };
+            return vec![vec![cell; self.cols]; self.rows];
+        }
+
+        let kind = self.digit_display.options().kind;
+        // Characters left to reveal by the typewriter
+        let mut unrevealed =
+            self.typewriter.then_some(self.typewriter_revealed);
+        let rows = self.wrapped_rows();
+        let clipped_rows = rows.len() > self.rows;
+        let mut board: Vec<Vec<_>> = rows
+            .into_iter()
+            .take(self.rows)
+            .enumerate()
+            .map(|(index, mut line)| {
+                let font = self.row_font(index);
+                let row_color = self.row_color(index);
+                if let Some(unrevealed) = &mut unrevealed {
+                    line.truncate(*unrevealed);
+                    *unrevealed -= line.len();
+                }
+                let mut row: Vec<_> = self
+                    .line_cells(&line)
+                    .into_iter()
+                    .map(|cell| {
+                        let mut glyph = font.glyph(&cell.ch);
+                        if cell.dp {
+                            glyph |= Segment::DP;
+                        }
+                        // Colors from the markup take precedence
+                        StyledCell {
+                            segments: kind.normalize(glyph),
+                            color: cell.color.or(row_color),
+                            ch: Some(cell.ch),
+                        }
+                    })
+                    .collect();
+                if !self.marquee && line.len() > self.cols {
+                    self.mark_clipped(&mut row, font);
+                }
+                row
+            })
+            .collect();
+        board.resize(self.rows, vec![StyledCell::BLANK; self.cols]);
+        // Rows past the board only count once the typewriter reaches them
+        if clipped_rows && unrevealed != Some(0) {
+            let font = self.row_font(self.rows.saturating_sub(1));
+            if let Some(row) = board.last_mut() {
+                self.mark_clipped(row, font);
+            }
+        }
+        board
+    }
+
+    /// Digit showing `cell` on the board. While the glyph editor is open,
+    /// hovering it shows its character and segments.
+    fn board_cell(&self, cell: StyledCell) -> iced::Element<'_, Message> {
+        use iced::widget as w;
+
+        let digit = self
+            .digit_display
+            .instantiate_with_fill(cell.segments, cell.color.map(Style::Solid));
+        if !self.editor {
+            return digit;
+        }
+        // Segments not taken from the font are named after a matching glyph
+        let ch = cell
+            .ch
+            .or_else(|| self.fonts[self.font].1.char_for(cell.segments));
+        let label = match ch {
+            Some(ch) => format!("{ch:?}: {}", cell.segments),
+            None => cell.segments.to_string(),
+        };
+        w::tooltip(digit, w::text(label).size(12.), w::tooltip::Position::Top)
+            .style(iced::theme::Container::Box)
+            .padding(4.)
+            .into()
+    }
+
+    /// Replaces the last cell of `row` by an arrow pointing past the edge
+    /// of the board, showing that the text does not fit.
+    fn mark_clipped(&self, row: &mut [StyledCell], font: &SegmentedFont) {
+        if !self.mark_clipped {
+            return;
+        }
+        let kind = self.digit_display.options().kind;
+        // Kinds without diagonal segments only show the decimal point
+        let (arrow, cell) = if self.rtl {
+            ('<', row.first_mut())
+        } else {
+            ('>', row.last_mut())
+        };
+        if let Some(cell) = cell {
+            *cell = StyledCell {
+                segments: kind.normalize(font.glyph(&arrow) | Segment::DP),
+                ..StyledCell::BLANK
+            };
+        }
+    }
+
+    /// Lines of the text shown on the board.
+    fn lines(&self) -> Vec<String> {
+        if self.clock {
+            vec![self.clock_text()]
+        } else if self.show_timer {
+            vec![self.timer.text(self.timer_now)]
+        } else {
+            self.text.lines().map(|line| line.to_string()).collect()
+        }
+    }
+
+    /// Cells of the text shown on the rows of the board.
+    fn text_rows(&self) -> Vec<Vec<Cell>> {
+        let mut rows = self.wrapped_rows();
+        rows.truncate(self.rows);
+        rows
+    }
+
+    /// Cells of all rows the text needs, including those past the last row
+    /// of the board. Long lines continue on the next rows if word wrapping
+    /// is enabled.
+    fn wrapped_rows(&self) -> Vec<Vec<Cell>> {
+        self.lines()
+            .iter()
+            .flat_map(|line| {
+                let cells = self.parse_line(line);
+                if self.word_wrap {
+                    markup::wrap(&cells, self.cols)
+                } else {
+                    vec![cells]
+                }
+            })
+            .collect()
+    }
+
+    /// Cells of a line of text, with the markup removed.
+    fn parse_line(&self, line: &str) -> Vec<Cell> {
+        let cells = markup::expand_control_chars(
+            markup::parse_line(line),
+            self.tab_width,
+            self.show_control_chars,
+        );
+        if self.merge_decimal_points {
+            markup::merge_decimal_points(cells)
+        } else {
+            cells
+        }
+    }
+
+    /// Font of the row at `index`, which is the selected font unless the row
+    /// has its own.
+    fn row_font(&self, index: usize) -> &SegmentedFont {
+        self.row_fonts
+            .get(index)
+            .and_then(Option::as_deref)
+            .and_then(|name| self.font_named(name))
+            .unwrap_or(&self.fonts[self.font].1)
+    }
+
+    fn font_named(&self, name: &str) -> Option<&SegmentedFont> {
+        self.fonts
+            .iter()
+            .find(|(font, _)| font == name)
+            .map(|(_, font)| font)
+    }
+
+    /// Attaches to every font the current glyphs of its fallback font, so
+    /// that edits of a font show in the fonts falling back to it.
+    fn link_fallback_fonts(&mut self) {
+        for index in 0..self.fonts.len() {
+            let name = self.fonts[index].0.as_str();
+            let base = self
+                .fallback_fonts
+                .get(name)
+                .and_then(|base| self.linked_font(base, &mut vec![name]));
+            self.fonts[index].1.set_fallback_font(base);
+        }
+    }
+
+    /// Copy of the font called `name` with its fallback fonts attached. A
+    /// font in `seen` falls back on itself and ends the chain instead.
+    fn linked_font<'a>(
+        &'a self,
+        name: &'a str,
+        seen: &mut Vec<&'a str>,
+    ) -> Option<Arc<SegmentedFont>> {
+        if seen.contains(&name) {
+            return None;
+        }
+        let mut font = self.font_named(name)?.clone();
+        seen.push(name);
+        let base = self
+            .fallback_fonts
+            .get(name)
+            .and_then(|base| self.linked_font(base, seen));
+        font.set_fallback_font(base);
+        Some(Arc::new(font))
+    }
+
+    /// Lets rows whose font no longer exists use the active font again.
+    fn forget_missing_row_fonts(&mut self) {
+        for index in 0..self.row_fonts.len() {
+            let missing = self.row_fonts[index]
+                .as_deref()
+                .is_some_and(|name| self.font_named(name).is_none());
+            if missing {
+                self.row_fonts[index] = None;
+            }
+        }
+    }
+
+    /// Color of the row at `index`, unless it uses the display color.
+    fn row_color(&self, index: usize) -> Option<Color> {
+        self.row_colors.get(index).copied().flatten()
+    }
+
+    /// Number of characters the typewriter reveals in total.
+    fn typewriter_len(&self) -> usize {
+        self.text_rows().iter().map(Vec::len).sum()
+    }
+
+    /// Digits of the clock with narrow colon cells between them.
+    fn clock_colon_cells(&self) -> Vec<iced::Element<'_, Message>> {
+        let font = self.row_font(0);
+        let kind = self.digit_display.options().kind;
+        // The colon is replaced by a space while it is off
+        self.clock_text()
+            .chars()
+            .map(|ch| match ch {
+                ':' | ' ' => self.digit_display.instantiate_colon(ch == ':'),
+                _ => self.digit_display.instantiate_with_fill(
+                    kind.normalize(font.glyph(&ch)),
+                    self.row_color(0).map(Style::Solid),
+                ),
+            })
+            .collect()
+    }
+
+    /// Current time as `HH:MM:SS`, or as `HH:MM` with the colon blinking
+    /// every second.
+    fn clock_text(&self) -> String {
+        use chrono::Timelike;
+
+        let format = if self.clock_seconds {
+            "%H:%M:%S"
+        } else if self.time.second().is_multiple_of(2) {
+            "%H:%M"
+        } else {
+            "%H %M"
+        };
+        self.time.format(format).to_string()
+    }
+
+    /// Characters shown in the cells of a row for the given line. Right to
+    /// left lines start in the last cell and are padded on the left.
+    fn line_cells(&self, line: &[Cell]) -> Vec<Cell> {
+        let mut cells = self.ltr_line_cells(line);
+        if self.rtl {
+            cells.reverse();
+        }
+        cells
+    }
+
+    fn ltr_line_cells(&self, line: &[Cell]) -> Vec<Cell> {
+        let len = line.len();
+        if self.marquee && len > self.cols {
+            // Scroll through the line, separated from its repetition by a gap
+            let period = len + self.marquee_gap;
+            line.iter()
+                .copied()
+                .chain(repeat_n(Cell::BLANK, self.marquee_gap))
+                .cycle()
+                .skip(self.marquee_offset % period)
+                .take(self.cols)
+                .collect()
+        } else {
+            line.iter()
+                .copied()
+                .chain(repeat(Cell::BLANK))
+                .take(self.cols)
+                .collect()
+        }
+    }
+
+    /// Scale of the board on screen.
+    fn zoom(&self) -> f32 {
+        self.digit_display.options().zoom
+    }
+
+    /// Largest zoom at which all columns fit into the width of the window.
+    fn fit_zoom(&self) -> f32 {
+        let options = self.digit_display.options();
+        let unscaled = self.cols as f32
+            * (options.size.width + self.char_spacing)
+            - self.char_spacing
+            + 2. * BOARD_PADDING;
+        let available = self.window_size[0] - 2. * WINDOW_PADDING - FIT_MARGIN;
+        (available / unscaled).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end())
+    }
+
+    /// Current look of the display as a profile.
+    fn current_profile(&self) -> Profile {
+        Profile {
+            background: self.background,
+            char_spacing: self.char_spacing,
+            line_spacing: self.line_spacing,
+            digit: self.digit_display.options().clone(),
+        }
+    }
+
+    fn apply_profile(&mut self, profile: Profile) {
+        self.background = profile.background;
+        self.char_spacing = profile.char_spacing;
+        self.line_spacing = profile.line_spacing;
+        self.digit_display.set_options(profile.digit);
+    }
+
+    /// Entered profile name, if it is not empty and not taken yet.
+    fn new_profile_name(&self) -> Option<String> {
+        let name = self.profile_name.trim();
+        (!name.is_empty() && !self.profiles.contains_key(name))
+            .then(|| name.to_string())
+    }
+
+    fn save_profiles(&self) {
+        if let Err(err) = profiles::save(&self.profiles) {
+            eprintln!("Failed to save the profiles: {err}");
+        }
+    }
+
+    /// Horizontal spacing between the digits on screen.
+    fn scaled_char_spacing(&self) -> f32 {
+        self.char_spacing * self.zoom()
+    }
+
+    /// Vertical spacing between the rows on screen.
+    fn scaled_line_spacing(&self) -> f32 {
+        self.line_spacing * self.zoom()
+    }
+
+    /// Padding around the board on screen.
+    fn board_padding(&self) -> f32 {
+        BOARD_PADDING * self.zoom()
+    }
+
+    /// Height of a row on the board, including the spacing below it.
+    fn row_height(&self) -> f32 {
+        let options = self.digit_display.options();
+        options.size.height * options.zoom + self.scaled_line_spacing()
+    }
+
+    /// Rows intersecting the visible region of the display. All rows are
+    /// considered visible until the viewport is known.
+    fn visible_rows(&self) -> std::ops::Range<usize> {
+        let Some(viewport) = self.viewport else {
+            return 0..self.rows;
+        };
+        let top = viewport.absolute_offset().y - self.board_padding();
+        let bottom = top + viewport.bounds().height;
+        rows_between(top, bottom, self.row_height(), self.rows)
+    }
+
+    /// Scrolls the display so that the given row is visible, if it is not
+    /// already.
+    fn scroll_to_row(&self, row: usize) -> iced::Command<Message> {
+        let Some(viewport) = self.viewport else {
+            return iced::Command::none();
+        };
+        let padding = self.board_padding();
+        let top = padding + row as f32 * self.row_height();
+        let bottom = top + self.row_height() - self.scaled_line_spacing();
+        let offset = viewport.absolute_offset();
+        let height = viewport.bounds().height;
+
+        let y = if top < offset.y {
+            top - padding
+        } else if bottom > offset.y + height {
+            bottom + padding - height
+        } else {
+            return iced::Command::none();
+        };
+        scrollable::scroll_to(
+            scrollable::Id::new(DISPLAY_SCROLLABLE),
+            scrollable::AbsoluteOffset { y, ..offset },
+        )
+    }
+
+    /// Writes the current board to the serial port if it changed since the
+    /// last frame.
+    fn stream_frame(&mut self) {
+        if self.serial.is_none() {
+            return;
+        }
+        let cells: Vec<_> = self.shown_board().into_iter().flatten().collect();
+        let frame = crate::serial::encode_frame(&cells);
+        if frame == self.serial_frame {
+            return;
+        }
+        if let Some(port) = &mut self.serial {
+            if let Err(err) = crate::serial::write_frame(port.as_mut(), &frame)
+            {
+                eprintln!("Failed to write to {}: {err}", self.serial_path);
+                self.serial = None;
+            }
+        }
+        self.serial_frame = frame;
+    }
+
+    /// Arrangement of the board in exported files.
+    fn export_layout(&self) -> segments::svg::SvgLayout {
+        segments::svg::SvgLayout {
+            char_spacing: self.char_spacing,
+            line_spacing: self.line_spacing,
+            padding: BOARD_PADDING,
+            background: self.background,
+        }
+    }
+
+    /// Writes the active font as JSON, to be loaded again on the next start.
+    fn save_font(&self, path: &std::path::Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(SEGMENTED_FONTS_DIR)?;
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.fonts[self.font].1.to_json(&mut out)?;
+        out.flush()
+    }
+
+    fn export_svg(&self, path: &str) -> std::io::Result<()> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        segments::svg::write_svg(
+            &mut out,
+            &self.export_board(),
+            self.digit_display.options(),
+            &self.export_layout(),
+        )?;
+        out.flush()
+    }
+
+    /// Renders the blinking and the marquee as an animated GIF. Frames are
+    /// stepped by the frame rate instead of being recorded in real time.
+    fn record_gif(&mut self, path: &str) -> Result<(), gif::EncodingError> {
+        let layout = self.export_layout();
+        let marquee_offset = self.marquee_offset;
+        let blink_on = self.digit_display.blink_on();
+        let mut frames = Vec::with_capacity(self.gif_frames);
+        for frame in 0..self.gif_frames {
+            let time = frame as f32 / self.gif_fps;
+            if self.marquee {
+                self.marquee_offset =
+                    marquee_offset + (time * self.marquee_speed) as usize;
+            }
+            // Each blink starts with its on phase
+            self.digit_display.set_blink_on(
+                !self.blink
+                    || ((time * 2. * self.blink_rate) as usize)
+                        .is_multiple_of(2),
+            );
+            frames.extend(segments::raster::rasterize(
+                &self.export_board(),
+                self.digit_display.options(),
+                &layout,
+            ));
+        }
+        self.marquee_offset = marquee_offset;
+        self.digit_display.set_blink_on(blink_on);
+
+        let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        segments::raster::write_gif(out, &frames, self.gif_fps)
+    }
+
+    /// Applies a message to the state. Separate from [`Application::update`],
+    /// which also tracks changes of the board after every message.
+    fn handle_message(&mut self, message: Message) -> iced::Command<Message> {
+        match message {
+            Message::FontLoaded { name, result } => {
+                if result.is_err() {
+                    eprintln!("Failed to load font {name}");
+                }
+                self.loading.increment();
+            }
+            Message::SetZoom(zoom) => {
+                self.auto_fit = false;
+                self.digit_display.modify_options(|o| o.zoom = zoom)
+            }
+            Message::SetAutoFit(auto_fit) => self.auto_fit = auto_fit,
+            Message::SetMirrorX(mirror_x) => {
+                self.digit_display.modify_options(|o| o.mirror_x = mirror_x)
+            }
+            Message::SetUpsideDown(upside_down) => self
+                .digit_display
+                .modify_options(|o| o.upside_down = upside_down),
+            Message::SetDigitWidth(v) => self
+                .digit_display
+                .modify_options(|o| o.resize(Size { width: v, ..o.size })),
+            Message::SetDigitHeight(v) => {
+                self.digit_display.modify_options(|o| {
+                    o.resize(Size {
+                        height: v,
+                        ..o.size
+                    })
+                })
+            }
+            Message::SetDigitThickness(v) => {
+                self.digit_display.modify_options(|o| o.thickness = v)
+            }
+            Message::SetDigitGap(v) => {
+                self.digit_display.modify_options(|o| o.gap = v)
+            }
+            Message::SetDigitAspect(v) => {
+                self.digit_display.modify_options(|o| o.aspect = v)
+            }
+            Message::SetDigitSlant(v) => {
+                self.digit_display.modify_options(|o| o.slant = v)
+            }
+            Message::SetDigitColor(color) => {
+                self.digit_display.modify_options(|o| match &mut o.on_fill {
+                    Style::Solid(solid) => *solid = color,
+                    Style::Gradient(Gradient::Linear(linear)) => {
+                        if let Some(top) = &mut linear.stops[0] {
+                            top.color = color;
+                        }
+                    }
+                })
+            }
+            Message::SetFade(fade) => self.digit_display.modify_options(|o| {
+                let color = top_color(&o.on_fill);
+                o.on_fill = if fade {
+                    o.vertical_gradient(color, dim(color))
+                } else {
+                    Style::Solid(color)
+                };
+            }),
+            Message::SetBrightness(brightness) => self
+                .digit_display
+                .modify_options(|o| o.brightness = brightness),
+            Message::SetRenderMode(mode) => {
+                self.digit_display.modify_options(|o| o.render_mode = mode)
+            }
+            Message::SetOutlineWidth(width) => self
+                .digit_display
+                .modify_options(|o| o.outline_width = width),
+            Message::SetCrisp(crisp) => {
+                self.digit_display.modify_options(|o| o.crisp = crisp)
+            }
+            Message::SetBatched(batched) => {
+                self.digit_display.modify_options(|o| o.batched = batched)
+            }
+            Message::SetGlow(glow) => {
+                self.digit_display.modify_options(|o| o.glow = glow)
+            }
+            Message::SetGlowIntensity(v) => {
+                self.digit_display.modify_options(|o| o.glow_intensity = v)
+            }
+            Message::SetGlowSpread(v) => {
+                self.digit_display.modify_options(|o| o.glow_spread = v)
+            }
+            Message::SetTransition(transition) => self
+                .digit_display
+                .modify_options(|o| o.transition = transition),
+            Message::SetTransitionDuration(duration) => self
+                .digit_display
+                .modify_options(|o| o.transition_duration = duration),
+            Message::SetFlicker(flicker) => {
+                self.digit_display.modify_options(|o| o.flicker = flicker)
+            }
+            Message::FlickerTick => self.digit_display.advance_flicker(),
+            Message::TransitionTick(now) => {
+                if self.transition_end.is_some_and(|end| now >= end) {
+                    self.transition_end = None;
+                }
+            }
+            Message::WindowResized(size) => {
+                self.window_size = [size.width, size.height];
+                if !self.fullscreen {
+                    self.windowed_size = self.window_size;
+                }
+            }
+            Message::WindowMoved(position) => {
+                if !self.fullscreen {
+                    self.window_position = Some([position.x, position.y]);
+                }
+            }
+            Message::ToggleFullscreen => {
+                self.fullscreen = !self.fullscreen;
+                let mode = if self.fullscreen {
+                    iced::window::Mode::Fullscreen
+                } else {
+                    iced::window::Mode::Windowed
+                };
+                let [width, height] = self.windowed_size;
+                return iced::Command::batch([
+                    iced::window::change_mode(iced::window::Id::MAIN, mode),
+                    if self.fullscreen {
+                        iced::Command::none()
+                    } else {
+                        iced::window::resize(
+                            iced::window::Id::MAIN,
+                            Size::new(width, height),
+                        )
+                    },
+                ]);
+            }
+            Message::ToggleControls => self.show_controls = !self.show_controls,
+            Message::SetTheme(theme) => self.theme = theme,
+            Message::ExitFullscreen => {
+                if self.fullscreen {
+                    return self.handle_message(Message::ToggleFullscreen);
+                }
+            }
+            Message::CloseRequested => {
+                let config = Config {
+                    window_size: self.windowed_size,
+                    window_position: self.window_position,
+                    fullscreen: self.fullscreen,
+                    theme: self.theme.clone(),
+                    background: self.background,
+                    char_spacing: self.char_spacing,
+                    line_spacing: self.line_spacing,
+                    digit: self.digit_display.options().clone(),
+                };
+                if let Err(err) = config.save() {
+                    eprintln!("Failed to save the config: {err}");
+                }
+                return iced::window::close(iced::window::Id::MAIN);
+            }
+            Message::ApplyDisplayTheme(theme) => {
+                self.digit_display
+                    .modify_options(|o| o.on_fill = theme.on_fill(o));
+                self.background = theme.background;
+            }
+            Message::SetBackground(color) => self.background = color,
+            Message::SelectProfile(name) => {
+                if let Some(profile) = self.profiles.get(&name).cloned() {
+                    self.apply_profile(profile);
+                    self.profile = Some(name);
+                }
+            }
+            Message::SaveProfile => {
+                if let Some(name) = &self.profile {
+                    self.profiles.insert(name.clone(), self.current_profile());
+                    self.save_profiles();
+                }
+            }
+            Message::SetProfileName(name) => self.profile_name = name,
+            Message::CreateProfile => {
+                if let Some(name) = self.new_profile_name() {
+                    self.profiles.insert(name.clone(), self.current_profile());
+                    self.profile = Some(name);
+                    self.profile_name.clear();
+                    self.save_profiles();
+                }
+            }
+            Message::RenameProfile => {
+                let old = self
+                    .profile
+                    .clone()
+                    .filter(|name| name != profiles::DEFAULT_PROFILE);
+                if let (Some(old), Some(new)) = (old, self.new_profile_name()) {
+                    if let Some(profile) = self.profiles.remove(&old) {
+                        self.profiles.insert(new.clone(), profile);
+                        self.profile = Some(new);
+                        self.profile_name.clear();
+                        self.save_profiles();
+                    }
+                }
+            }
+            Message::DeleteProfile => {
+                if let Some(name) = self.profile.take() {
+                    if name == profiles::DEFAULT_PROFILE {
+                        self.profile = Some(name);
+                    } else {
+                        self.profiles.remove(&name);
+                        self.save_profiles();
+                    }
+                }
+            }
+            Message::SetCharSpacing(spacing) => self.char_spacing = spacing,
+            Message::SetLineSpacing(spacing) => self.line_spacing = spacing,
+            Message::SetRows(rows) => self.rows = rows.max(1),
+            Message::SetCols(cols) => self.cols = cols.max(1),
+            Message::TextAreaAction(action) => {
+                self.text.perform(action);
+                let (line, index) = self.text.cursor_position();
+                // The character after the cursor, or before it at the end of
+                // the line
+                self.cursor_char = self.text.line(line).and_then(|text| {
+                    let (before, after) = text.split_at(index.min(text.len()));
+                    after.chars().next().or_else(|| before.chars().next_back())
+                });
+                let row = line.min(self.rows.saturating_sub(1));
+                return self.scroll_to_row(
+                    self.digit_display.options().board_row(row, self.rows),
+                );
+            }
+            Message::ClearText => {
+                self.text = iced::widget::text_editor::Content::new();
+                self.cursor_char = None;
+                return self.scroll_to_row(
+                    self.digit_display.options().board_row(0, self.rows),
+                );
+            }
+            Message::SetBlink(blink) => {
+                self.blink = blink;
+                self.digit_display.set_blink_on(true);
+            }
+            Message::SetBlinkRate(rate) => self.blink_rate = rate,
+            Message::Tick => {
+                let blink_on = self.digit_display.blink_on();
+                self.digit_display.set_blink_on(!blink_on);
+            }
+            Message::SetRtl(rtl) => self.rtl = rtl,
+            Message::SetMergeDecimalPoints(merge) => {
+                self.merge_decimal_points = merge
+            }
+            Message::SetTabWidth(width) => self.tab_width = width,
+            Message::SetWordWrap(word_wrap) => self.word_wrap = word_wrap,
+            Message::SetMarkClipped(mark) => self.mark_clipped = mark,
+            Message::SetShowControlChars(show) => {
+                self.show_control_chars = show
+            }
+            Message::SetMarquee(marquee) => {
+                self.marquee = marquee;
+                self.marquee_offset = 0;
+            }
+            Message::SetMarqueeSpeed(speed) => self.marquee_speed = speed,
+            Message::SetMarqueeGap(gap) => self.marquee_gap = gap,
+            Message::MarqueeTick => {
+                self.marquee_offset = self.marquee_offset.wrapping_add(1)
+            }
+            Message::SetTypewriter(typewriter) => {
+                self.typewriter = typewriter;
+                self.typewriter_revealed = 0;
+            }
+            Message::SetTypewriterSpeed(speed) => self.typewriter_speed = speed,
+            Message::SetTypewriterLoop(repeat) => self.typewriter_loop = repeat,
+            Message::TypewriterTick => {
+                // A looping text stays complete for a few ticks before
+                // starting over
+                if self.typewriter_revealed
+                    >= self.typewriter_len() + TYPEWRITER_LOOP_PAUSE
+                {
+                    self.typewriter_revealed = 0;
+                } else {
+                    self.typewriter_revealed += 1;
+                }
+            }
+            Message::SetClock(clock) => {
+                self.clock = clock;
+                self.show_timer &= !clock;
+                self.time = chrono::Local::now();
+            }
+            Message::SetClockSeconds(seconds) => self.clock_seconds = seconds,
+            Message::SetClockColons(colons) => self.clock_colons = colons,
+            Message::ClockTick => self.time = chrono::Local::now(),
+            Message::SetTimer(show_timer) => {
+                self.show_timer = show_timer;
+                self.clock &= !show_timer;
+            }
+            Message::SetTimerMode(mode) => {
+                self.timer.mode = mode;
+                self.timer.reset();
+            }
+            Message::SetTimerDuration(duration) => {
+                self.timer.duration = duration;
+                self.timer.reset();
+            }
+            Message::StartTimer => {
+                self.timer_now = Instant::now();
+                // A finished countdown starts over
+                if self.timer.is_finished(self.timer_now) {
+                    self.timer.reset();
+                }
+                self.timer.start(self.timer_now);
+            }
+            Message::PauseTimer => {
+                self.timer_now = Instant::now();
+                self.timer.pause(self.timer_now);
+            }
+            Message::ResetTimer => self.timer.reset(),
+            Message::TimerTick(now) => {
+                self.timer_now = now;
+                if self.timer.is_running() && self.timer.is_finished(now) {
+                    self.timer.pause(now);
+                    return iced::Command::perform(
+                        std::future::ready(()),
+                        |()| Message::TimerFinished,
+                    );
+                }
+            }
+            // Flash the display to draw attention to it
+            Message::TimerFinished => {
+                return self.handle_message(Message::SetBlink(true))
+            }
+            Message::SetTestPattern(pattern) => {
+                self.test_pattern = pattern;
+                self.test_segment = 0;
+            }
+            Message::TestTick => {
+                self.test_segment =
+                    (self.test_segment + 1) % segments::SEGMENT_COUNT
+            }
+            Message::SelectFont(font) => {
+                self.font = font;
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+            }
+            Message::SetRowFont(row, font) => {
+                if self.row_fonts.len() <= row {
+                    self.row_fonts.resize(row + 1, None);
+                }
+                self.row_fonts[row] = font;
+                self.forget_missing_row_fonts();
+            }
+            Message::SetRowColor(row, color) => {
+                if self.row_colors.len() <= row {
+                    self.row_colors.resize(row + 1, None);
+                }
+                self.row_colors[row] = color;
+            }
+            Message::SetDisplayKind(kind) => {
+                self.digit_display.modify_options(|o| o.kind = kind);
+                self.editor_display.modify_options(|o| o.kind = kind);
+            }
+            Message::SetFallbackGlyph(fallback) => {
+                let font = &mut self.fonts[self.font].1;
+                let bits = match fallback {
+                    FallbackGlyph::Blank => None,
+                    FallbackGlyph::AllSegments => Some(SegmentBits::all()),
+                    FallbackGlyph::Replacement => font.get(&'?').copied(),
+                };
+                font.set_fallback_glyph(bits);
+            }
+            Message::SetFallbackFont(base) => {
+                let name = &self.fonts[self.font].0;
+                match base {
+                    Some(base) => {
+                        self.fallback_fonts.insert(name.clone(), base)
+                    }
+                    None => self.fallback_fonts.remove(name),
+                };
+                self.link_fallback_fonts();
+            }
+            Message::SetEditor(editor) => self.editor = editor,
+            Message::SetEditorChar(input) => {
+                if let Some(ch) = input.chars().last() {
+                    self.editor_char = ch;
+                }
+            }
+            Message::EditGlyph(segments) => {
+                let ch = self.editor_char;
+                let old = self.fonts[self.font].1.insert(ch, segments);
+                if old != Some(segments) {
+                    if self.undo_stack.len() == GLYPH_HISTORY_LEN {
+                        self.undo_stack.remove(0);
+                    }
+                    self.undo_stack.push(GlyphEdit {
+                        ch,
+                        old,
+                        new: segments,
+                    });
+                    self.redo_stack.clear();
+                    self.link_fallback_fonts();
+                }
+            }
+            Message::SetDamageEditor(editor) => self.damage_editor = editor,
+            Message::SetRawSegments(raw) => self.raw_segments = raw,
+            Message::SetDeadSegments(segments) => {
+                self.digit_display.set_dead_segments(segments)
+            }
+            Message::SetStuckSegments(segments) => {
+                self.digit_display.set_stuck_segments(segments)
+            }
+            Message::UndoGlyphEdit if self.editor => {
+                if let Some(edit) = self.undo_stack.pop() {
+                    let font = &mut self.fonts[self.font].1;
+                    match edit.old {
+                        Some(old) => font.insert(edit.ch, old),
+                        None => font.remove(&edit.ch),
+                    };
+                    self.editor_char = edit.ch;
+                    self.redo_stack.push(edit);
+                    self.link_fallback_fonts();
+                }
+            }
+            Message::RedoGlyphEdit if self.editor => {
+                if let Some(edit) = self.redo_stack.pop() {
+                    self.fonts[self.font].1.insert(edit.ch, edit.new);
+                    self.editor_char = edit.ch;
+                    self.undo_stack.push(edit);
+                    self.link_fallback_fonts();
+                }
+            }
+            // Shortcuts only edit the font while the editor is open
+            Message::UndoGlyphEdit | Message::RedoGlyphEdit => (),
+            Message::SaveFont => {
+                let path = std::path::Path::new(SEGMENTED_FONTS_DIR)
+                    .join(format!("{}.json", self.fonts[self.font].0));
+                if let Err(err) = self.save_font(&path) {
+                    eprintln!("Failed to save {}: {err}", path.display());
+                }
+            }
+            Message::ExportSvg => {
+                if let Err(err) = self.export_svg(SVG_EXPORT_PATH) {
+                    eprintln!("Failed to export {SVG_EXPORT_PATH}: {err}");
+                }
+            }
+            Message::CopyAscii => {
+                return iced::clipboard::write(
+                    segments::ascii::board_to_ascii(
+                        &self.shown_board(),
+                        self.digit_display.options().kind,
+                    ),
+                );
+            }
+            Message::SetGifFrames(frames) => self.gif_frames = frames,
+            Message::SetGifFps(fps) => self.gif_fps = fps,
+            Message::RecordGif => {
+                if let Err(err) = self.record_gif(GIF_EXPORT_PATH) {
+                    eprintln!("Failed to record {GIF_EXPORT_PATH}: {err}");
+                }
+            }
+            Message::Scrolled(viewport) => self.viewport = Some(viewport),
+            Message::SetSerialPath(path) => self.serial_path = path,
+            Message::SetSerialOutput(enabled) => {
+                self.serial = None;
+                self.serial_frame.clear();
+                if enabled {
+                    match crate::serial::open(&self.serial_path) {
+                        Ok(port) => self.serial = Some(port),
+                        Err(err) => eprintln!(
+                            "Failed to open {}: {err}",
+                            self.serial_path
+                        ),
+                    }
+                }
+            }
+            Message::SerialTick => self.stream_frame(),
+            Message::SetRemote(remote) => self.remote = remote,
+            Message::RemoteText(line) => {
+                let mut lines: Vec<String> =
+                    self.text.lines().map(|line| line.to_string()).collect();
+                if lines.iter().all(|line| line.is_empty()) {
+                    lines.clear();
+                }
+                lines.push(line);
+                let scrolled = lines.len().saturating_sub(self.rows);
+                self.text = iced::widget::text_editor::Content::with_text(
+                    &lines[scrolled..].join("\n"),
+                );
+            }
+            Message::WatchedFile(contents) => match contents {
+                Ok(contents) => {
+                    self.text = iced::widget::text_editor::Content::with_text(
+                        contents.trim_end_matches('\n'),
+                    );
+                    self.watch_error = None;
+                }
+                // The last contents stay visible
+                Err(err) => self.watch_error = Some(err),
+            },
+        }
+        iced::Command::none()
+    }
 }
 
 impl Application for CatoDisplayApp {
     type Executor = iced::executor::Default;
     type Theme = iced::Theme;
-    type Flags = ();
+    type Flags = Flags;
     type Message = Message;
 
-    fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+    fn new(flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+        let (num_dir_fonts, load_dir_fonts) =
+            match crate::fonts::load_fonts_from_dir(crate::fonts::FONTS_DIR) {
+                Ok(loaded) => loaded,
+                Err(err) => {
+                    if err.kind() != std::io::ErrorKind::NotFound {
+                        eprintln!(
+                            "Failed to read {}: {err}",
+                            crate::fonts::FONTS_DIR
+                        );
+                    }
+                    (0, iced::Command::none())
+                }
+            };
+
         (
             Self {
                 loading: LoadingStatus::with_total(
-                    crate::fonts::NUM_FONTS as u32,
+                    (crate::fonts::NUM_FONTS + num_dir_fonts) as u32,
                 ),
-                digit_display: segments::DigitDisplay::new(DigitOptions {
+                digit_display: segments::DigitDisplay::new(
+                    flags.config.digit.clone(),
+                ),
+                text: flags.text.as_deref().map_or_else(
+                    Default::default,
+                    iced::widget::text_editor::Content::with_text,
+                ),
+                rows: 4,
+                cols: 24,
+                background: flags.config.background,
+                profiles: profiles::load().unwrap_or_else(|err| {
+                    eprintln!("Failed to load the profiles: {err}");
+                    profiles::presets()
+                }),
+                profile: None,
+                profile_name: String::new(),
+                char_spacing: flags.config.char_spacing,
+                line_spacing: flags.config.line_spacing,
+                window_size: flags.config.window_size,
+                windowed_size: flags.config.window_size,
+                window_position: flags.config.window_position,
+                fullscreen: flags.config.fullscreen,
+                theme: flags.config.theme.clone(),
+                show_controls: true,
+                auto_fit: false,
+                blink: false,
+                blink_rate: 1.,
+                rtl: false,
+                merge_decimal_points: false,
+                tab_width: markup::TAB_WIDTH,
+                show_control_chars: true,
+                word_wrap: false,
+                mark_clipped: true,
+                marquee: false,
+                marquee_speed: 4.,
+                marquee_gap: 4,
+                marquee_offset: 0,
+                typewriter: false,
+                typewriter_speed: 8.,
+                typewriter_loop: false,
+                typewriter_revealed: 0,
+                fonts: load_segmented_fonts(),
+                font: 0,
+                row_fonts: Vec::new(),
+                fallback_fonts: HashMap::new(),
+                row_colors: Vec::new(),
+                editor: false,
+                editor_char: 'A',
+                editor_display: segments::DigitDisplay::new(DigitOptions {
+                    size: Size::new(120., 240.),
+                    thickness: 16.,
+                    gap: 3.,
+                    off_fill: Some(Style::Solid(Color::from_rgb(0.2, 0., 0.))),
                     ..Default::default()
                 }),
-                text: Default::default(),
+                damage_editor: false,
+                raw_segments: String::new(),
+                cursor_char: None,
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                viewport: None,
+                serial_path: "/dev/ttyUSB0".into(),
+                serial: None,
+                serial_frame: Vec::new(),
+                gif_frames: 40,
+                gif_fps: 10.,
+                remote: false,
+                stdin: flags.stdin,
+                watch: flags.watch,
+                watch_error: None,
+                clock: false,
+                clock_seconds: true,
+                clock_colons: false,
+                time: chrono::Local::now(),
+                show_timer: false,
+                timer: Timer::new(TimerMode::Countdown, TIMER_DURATION),
+                timer_now: Instant::now(),
+                test_pattern: TestPattern::Off,
+                test_segment: 0,
+                transition_board: Vec::new(),
+                transition_end: None,
             },
-            crate::fonts::load_fonts(),
+            iced::Command::batch([
+                crate::fonts::load_fonts(),
+                load_dir_fonts,
+                if flags.config.fullscreen {
+                    iced::window::change_mode(
+                        iced::window::Id::MAIN,
+                        iced::window::Mode::Fullscreen,
+                    )
+                } else {
+                    iced::Command::none()
+                },
+            ]),
         )
     }
 
@@ -74,30 +1679,170 @@ impl Application for CatoDisplayApp {
     }
 
     fn theme(&self) -> Self::Theme {
-        iced::Theme::TokyoNight
+        self.theme.clone()
     }
 
     fn update(
         &mut self,
         message: Self::Message,
     ) -> iced::Command<Self::Message> {
-        match message {
-            Message::FontLoaded { name, result } => {
-                if result.is_err() {
-                    eprintln!("Failed to load font {name}");
-                }
-                self.loading.increment();
+        let command = self.handle_message(message);
+        if self.auto_fit {
+            // Also refits after changes to the number of columns or the
+            // size of the digits
+            let zoom = self.fit_zoom();
+            if zoom != self.zoom() {
+                self.digit_display.modify_options(|o| o.zoom = zoom);
             }
-            Message::SetDigitThickness(v) => {
-                self.digit_display.modify_options(|o| o.thickness = v)
-            }
-            Message::SetDigitGap(v) => {
-                self.digit_display.modify_options(|o| o.gap = v)
+        }
+        if self.digit_display.options().transition {
+            // Keep redrawing until the crossfade of the changed digits ends
+            let board = self.board();
+            if board != self.transition_board {
+                self.transition_board = board;
+                self.transition_end = Some(
+                    Instant::now()
+                        + Duration::from_secs_f32(
+                            self.digit_display.options().transition_duration,
+                        ),
+                );
             }
-            Message::TextAreaAction(action) => self.text.perform(action),
-            Message::Scrolled(_viewport) => (),
         }
-        iced::Command::none()
+        command
+    }
+
+    fn subscription(&self) -> iced::Subscription<Self::Message> {
+        let mut subscriptions =
+            vec![iced::event::listen_with(|event, _status| match event {
+                iced::Event::Window(
+                    _,
+                    iced::window::Event::Resized { width, height },
+                ) => Some(Message::WindowResized(Size::new(
+                    width as f32,
+                    height as f32,
+                ))),
+                iced::Event::Window(_, iced::window::Event::Moved { x, y }) => {
+                    Some(Message::WindowMoved(iced::Point::new(
+                        x as f32, y as f32,
+                    )))
+                }
+                iced::Event::Window(_, iced::window::Event::CloseRequested) => {
+                    Some(Message::CloseRequested)
+                }
+                iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    key: iced::keyboard::Key::Named(key),
+                    ..
+                }) => match key {
+                    iced::keyboard::key::Named::F11 => {
+                        Some(Message::ToggleFullscreen)
+                    }
+                    iced::keyboard::key::Named::Escape => {
+                        Some(Message::ExitFullscreen)
+                    }
+                    _ => None,
+                },
+                iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    key: iced::keyboard::Key::Character(key),
+                    modifiers,
+                    ..
+                }) if modifiers.command() => {
+                    match key.to_lowercase().as_str() {
+                        "l" => Some(Message::ClearText),
+                        "h" => Some(Message::ToggleControls),
+                        "z" if modifiers.shift() => {
+                            Some(Message::RedoGlyphEdit)
+                        }
+                        "z" => Some(Message::UndoGlyphEdit),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })];
+
+        if self.blink {
+            // Each blink consists of an on and an off phase
+            subscriptions.push(
+                iced::time::every(Duration::from_secs_f32(
+                    0.5 / self.blink_rate,
+                ))
+                .map(|_| Message::Tick),
+            );
+        }
+
+        if self.marquee {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs_f32(
+                    1. / self.marquee_speed,
+                ))
+                .map(|_| Message::MarqueeTick),
+            );
+        }
+
+        if self.typewriter
+            && (self.typewriter_loop
+                || self.typewriter_revealed < self.typewriter_len())
+        {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs_f32(
+                    1. / self.typewriter_speed,
+                ))
+                .map(|_| Message::TypewriterTick),
+            );
+        }
+
+        if self.serial.is_some() {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs_f32(
+                    1. / SERIAL_FRAME_RATE,
+                ))
+                .map(|_| Message::SerialTick),
+            );
+        }
+
+        if self.show_timer && self.timer.is_running() {
+            subscriptions.push(
+                iced::time::every(TIMER_TICK_INTERVAL).map(Message::TimerTick),
+            );
+        }
+
+        if self.clock {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(1))
+                    .map(|_| Message::ClockTick),
+            );
+        }
+
+        if self.test_pattern == TestPattern::Cycle {
+            subscriptions.push(
+                iced::time::every(TEST_CYCLE_INTERVAL)
+                    .map(|_| Message::TestTick),
+            );
+        }
+
+        if self.remote {
+            subscriptions.push(crate::remote::listen(crate::remote::PORT));
+        }
+
+        if self.stdin {
+            subscriptions.push(crate::stdin::read_lines());
+        }
+        if let Some(path) = &self.watch {
+            subscriptions.push(crate::watch::watch(path.clone()));
+        }
+
+        if self.digit_display.options().flicker > 0. {
+            subscriptions.push(
+                iced::time::every(FLICKER_INTERVAL)
+                    .map(|_| Message::FlickerTick),
+            );
+        }
+
+        if self.transition_end.is_some() {
+            subscriptions
+                .push(iced::window::frames().map(Message::TransitionTick));
+        }
+
+        iced::Subscription::batch(subscriptions)
     }
 
     fn view(
@@ -119,46 +1864,159 @@ impl Application for CatoDisplayApp {
                 .into();
         }
 
-        let font = &*segments::segmented_font::DEFAULT;
         let display = {
-            const H_SPACING: f32 = 8.;
+            let options = self.digit_display.options();
+            let cell = options.zoomed_size();
+            let (char_spacing, padding) =
+                (self.scaled_char_spacing(), self.board_padding());
+            let spacer_width =
+                self.cols as f32 * (cell.width + char_spacing) - char_spacing;
+            let board_width = spacer_width + 2. * padding;
+            let visible = self.visible_rows();
+            let background = self.background;
 
-            let mut display =
-                w::column(self.text.lines().take(4).map(|line| {
-                    w::row(line.chars().chain(repeat(' ')).take(24).map(|ch| {
-                        self.digit_display.instantiate(
-                            font.get(&ch).cloned().unwrap_or_default(),
-                        )
-                    }))
-                    .spacing(H_SPACING)
-                    .clip(true)
+            w::responsive(move |bounds| {
+                let mut board: Vec<_> =
+                    self.styled_board().into_iter().enumerate().collect();
+                if options.upside_down {
+                    board.reverse();
+                }
+                // Rows outside of the viewport are replaced by empty spacers
+                let display = w::column(board.into_iter().enumerate().map(
+                    |(index, (row, cells))| {
+                        if !visible.contains(&index) {
+                            return w::Space::new(spacer_width, cell.height)
+                                .into();
+                        }
+                        let mut cells: Vec<_> =
+                            if row == 0 && self.clock && self.clock_colons {
+                                self.clock_colon_cells()
+                            } else {
+                                cells
+                                    .into_iter()
+                                    .map(|cell| self.board_cell(cell))
+                                    .collect()
+                            };
+                        if options.mirror_x != options.upside_down {
+                            cells.reverse();
+                        }
+                        w::row(cells).spacing(char_spacing).clip(true).into()
+                    },
+                ))
+                .spacing(self.scaled_line_spacing());
+
+                let display = w::container(display)
+                    .width(Length::Shrink)
+                    .padding(padding)
+                    .style(move |theme: &iced::Theme| {
+                        w::container::Appearance::default()
+                            .with_background(background)
+                            .with_border(
+                                theme.extended_palette().secondary.weak.color,
+                                4.,
+                            )
+                    });
+                // Centered while the board fits, scrolled once zoomed in
+                // beyond the available width
+                let display = w::container(display)
+                    .width(board_width.max(bounds.width))
+                    .center_x();
+                w::scrollable(display)
+                    .id(scrollable::Id::new(DISPLAY_SCROLLABLE))
+                    .direction(scrollable::Direction::Both {
+                        vertical: scrollable::Properties::default(),
+                        horizontal: scrollable::Properties::default(),
+                    })
+                    .on_scroll(Message::Scrolled)
+                    .height(Length::Fill)
                     .into()
-                }))
-                .spacing(16.);
-
-            for _ in 0..4usize.saturating_sub(self.text.line_count()) {
-                display = display.push(
-                    w::row((0..24).map(|_| {
-                        self.digit_display.instantiate(Default::default())
-                    }))
-                    .spacing(H_SPACING),
-                );
-            }
-            let display = w::container(display)
-                .width(Length::Shrink)
-                .padding(8.)
-                .style(|theme: &iced::Theme| {
-                    w::container::Appearance::default()
-                        .with_background(Color::BLACK)
-                        .with_border(
-                            theme.extended_palette().secondary.weak.color,
-                            4.,
-                        )
-                });
-            let display = w::container(display).width(Length::Fill).center_x();
-            w::scrollable(display)
-                .on_scroll(Message::Scrolled)
-                .height(Length::Fill)
+            })
+        };
+
+        // Kiosk mode for signage, without any controls
+        if self.fullscreen {
+            return w::container(display).padding(WINDOW_PADDING).into();
+        }
+        if !self.show_controls {
+            let show = w::button("Show controls (Ctrl+H)")
+                .on_press(Message::ToggleControls);
+            return w::container(w::column!(show, display).spacing(16.))
+                .padding(WINDOW_PADDING)
+                .into();
+        }
+
+        let board_size = {
+            let display = w::text(format!("{}×{} cells", self.cols, self.rows))
+                .width(80.);
+            let cols = w::slider(1. ..=64., self.cols as f32, |v| {
+                Message::SetCols(v as usize)
+            });
+            let rows = w::slider(1. ..=16., self.rows as f32, |v| {
+                Message::SetRows(v as usize)
+            });
+            w::row!(display, cols, rows).spacing(4.)
+        };
+
+        let spacing = {
+            let display = w::text(format!(
+                "{:.0}, {:.0}",
+                self.char_spacing, self.line_spacing
+            ))
+            .width(80.);
+            let char_spacing = w::slider(
+                0. ..=64.,
+                self.char_spacing,
+                Message::SetCharSpacing,
+            );
+            let line_spacing = w::slider(
+                0. ..=64.,
+                self.line_spacing,
+                Message::SetLineSpacing,
+            );
+            w::row!(display, char_spacing, line_spacing).spacing(4.)
+        };
+
+        let zoom = {
+            let zoom = self.zoom();
+            let display = w::text(format!("{:.0}%", zoom * 100.)).width(80.);
+            let slider =
+                w::slider(ZOOM_RANGE, zoom, Message::SetZoom).step(0.05);
+            let auto_fit = w::checkbox("Fit width", self.auto_fit)
+                .on_toggle(Message::SetAutoFit);
+            let options = self.digit_display.options();
+            let mirror_x = w::checkbox("Mirror", options.mirror_x)
+                .on_toggle(Message::SetMirrorX);
+            let upside_down = w::checkbox("Upside down", options.upside_down)
+                .on_toggle(Message::SetUpsideDown);
+            let fullscreen = w::button("Full screen (F11, Esc to leave)")
+                .on_press(Message::ToggleFullscreen);
+            let hide = w::button("Hide controls (Ctrl+H)")
+                .on_press(Message::ToggleControls);
+            w::row!(
+                display,
+                slider,
+                auto_fit,
+                mirror_x,
+                upside_down,
+                fullscreen,
+                hide
+            )
+            .spacing(16.)
+        };
+
+        let size = {
+            let size = self.digit_display.options().size;
+            let width =
+                w::slider(10. ..=200., size.width, Message::SetDigitWidth);
+            let height =
+                w::slider(10. ..=200., size.height, Message::SetDigitHeight);
+            w::row!(
+                w::text(format!("{:.0}×{:.0}", size.width, size.height))
+                    .width(80.),
+                width,
+                height
+            )
+            .spacing(4.)
         };
 
         let thickness = {
@@ -175,16 +2033,882 @@ impl Application for CatoDisplayApp {
             let gap = self.digit_display.options().gap;
             let display = w::text(format!("{gap:.2}")).width(80.);
             let slider =
-                w::slider(1. ..=100., gap, Message::SetDigitGap).step(0.1);
+                w::slider(-20. ..=20., gap, Message::SetDigitGap).step(0.1);
             w::row!(display, slider).spacing(4.)
         };
 
-        let input =
-            w::text_editor(&self.text).on_action(Message::TextAreaAction);
+        let slant = {
+            let slant = self.digit_display.options().slant;
+            let display = w::text(format!("{slant:.2}")).width(80.);
+            let slider =
+                w::slider(-0.5..=0.5, slant, Message::SetDigitSlant).step(0.01);
+            w::row!(display, slider).spacing(4.)
+        };
+
+        let aspect = {
+            let aspect = self.digit_display.options().aspect;
+            let display = w::text(format!("{aspect:.2}×")).width(80.);
+            let slider = w::slider(0.5..=1.5, aspect, Message::SetDigitAspect)
+                .step(0.01);
+            w::row!(display, slider).spacing(4.)
+        };
+
+        let color = {
+            let on_fill = &self.digit_display.options().on_fill;
+            let color = top_color(on_fill);
+            let fade = w::checkbox(
+                "Fade towards the bottom",
+                matches!(on_fill, Style::Gradient(_)),
+            )
+            .on_toggle(Message::SetFade);
+            let brightness = self.digit_display.options().brightness;
+            let display =
+                w::text(format!("{:.0} %", brightness * 100.)).width(80.);
+            let slider =
+                w::slider(0. ..=1., brightness, Message::SetBrightness)
+                    .step(0.01);
+            w::column!(
+                color_sliders(color, Message::SetDigitColor),
+                w::row!(display, slider).spacing(4.),
+                fade
+            )
+            .spacing(4.)
+        };
+
+        let background = color_sliders(self.background, Message::SetBackground);
+
+        let render_mode = {
+            let options = self.digit_display.options();
+            // Meshes are only offered while the renderer can draw them
+            let modes: Vec<_> = segments::RenderMode::ALL
+                .into_iter()
+                .filter(|&mode| {
+                    mode != segments::RenderMode::Mesh
+                        || segments::meshes_supported()
+                })
+                .collect();
+            let list = w::pick_list(
+                modes,
+                Some(options.render_mode),
+                Message::SetRenderMode,
+            );
+            let width = w::slider(
+                0.25..=8.,
+                options.outline_width,
+                Message::SetOutlineWidth,
+            )
+            .step(0.25);
+            let crisp = w::checkbox("Crisp edges", options.crisp)
+                .on_toggle(Message::SetCrisp);
+            let batched = w::checkbox("Batch paths", options.batched)
+                .on_toggle(Message::SetBatched);
+            let fallback = (options.render_mode == segments::RenderMode::Mesh
+                && !segments::meshes_supported())
+            .then(|| w::text("No GPU, drawing paths instead"));
+            w::row!(
+                w::text("Rendering").width(80.),
+                list,
+                crisp,
+                batched,
+                w::text("Outline"),
+                width
+            )
+            .push_maybe(fallback)
+            .spacing(4.)
+        };
+
+        let glow = {
+            let options = self.digit_display.options();
+            let toggle =
+                w::checkbox("Glow", options.glow).on_toggle(Message::SetGlow);
+            let intensity = w::slider(
+                0. ..=1.,
+                options.glow_intensity,
+                Message::SetGlowIntensity,
+            )
+            .step(0.01);
+            let spread = w::slider(
+                0. ..=8.,
+                options.glow_spread,
+                Message::SetGlowSpread,
+            )
+            .step(0.1);
+            w::row!(
+                w::container(toggle).width(80.),
+                w::text("Intensity"),
+                intensity,
+                w::text("Spread"),
+                spread
+            )
+            .spacing(4.)
+        };
+
+        let transition = {
+            let options = self.digit_display.options();
+            let toggle = w::checkbox("Crossfade", options.transition)
+                .on_toggle(Message::SetTransition);
+            let duration = w::slider(
+                0.05..=1.,
+                options.transition_duration,
+                Message::SetTransitionDuration,
+            )
+            .step(0.05);
+            w::row!(
+                w::container(toggle).width(80.),
+                w::text(format!("{:.2} s", options.transition_duration)),
+                duration
+            )
+            .spacing(4.)
+        };
+
+        let flicker = {
+            let flicker = self.digit_display.options().flicker;
+            w::row!(
+                w::text("Flicker").width(80.),
+                w::text(format!("{:.0} %", flicker * 100.)),
+                w::slider(0. ..=1., flicker, Message::SetFlicker).step(0.05)
+            )
+            .spacing(4.)
+        };
+
+        let display_theme = {
+            let options = self.digit_display.options();
+            let selected = DISPLAY_THEMES.into_iter().find(|theme| {
+                options.on_fill == theme.on_fill(options)
+                    && self.background == theme.background
+            });
+            let list = w::pick_list(
+                DISPLAY_THEMES,
+                selected,
+                Message::ApplyDisplayTheme,
+            )
+            .placeholder("Custom");
+            let theme = w::pick_list(
+                iced::Theme::ALL,
+                Some(self.theme.clone()),
+                Message::SetTheme,
+            );
+            w::row!(
+                w::text("Colors").width(80.),
+                list,
+                w::text("Interface").width(80.),
+                theme
+            )
+            .spacing(4.)
+        };
+
+        let profile = {
+            let list = w::pick_list(
+                self.profiles.keys().cloned().collect::<Vec<_>>(),
+                self.profile.clone(),
+                Message::SelectProfile,
+            )
+            .placeholder("Custom");
+            let save = w::button("Save").on_press_maybe(
+                self.profile.is_some().then_some(Message::SaveProfile),
+            );
+            let name = w::text_input("Profile name", &self.profile_name)
+                .on_input(Message::SetProfileName)
+                .width(160.);
+            let valid_name = self.new_profile_name().is_some();
+            let create = w::button("Create")
+                .on_press_maybe(valid_name.then_some(Message::CreateProfile));
+            let renamable = self
+                .profile
+                .as_ref()
+                .is_some_and(|name| name != profiles::DEFAULT_PROFILE);
+            let rename = w::button("Rename").on_press_maybe(
+                (valid_name && renamable).then_some(Message::RenameProfile),
+            );
+            let delete = w::button("Delete")
+                .on_press_maybe(renamable.then_some(Message::DeleteProfile));
+            w::row!(
+                w::text("Profile").width(80.),
+                list,
+                save,
+                name,
+                create,
+                rename,
+                delete
+            )
+            .spacing(4.)
+        };
+
+        let blink = {
+            let toggle =
+                w::checkbox("Blink", self.blink).on_toggle(Message::SetBlink);
+            let display =
+                w::text(format!("{:.2} Hz", self.blink_rate)).width(80.);
+            let slider =
+                w::slider(0.1..=10., self.blink_rate, Message::SetBlinkRate)
+                    .step(0.1);
+            w::row!(toggle, display, slider).spacing(4.)
+        };
+
+        let marquee = {
+            let toggle = w::checkbox("Marquee", self.marquee)
+                .on_toggle(Message::SetMarquee);
+            let speed =
+                w::text(format!("{:.1} ch/s", self.marquee_speed)).width(80.);
+            let speed_slider = w::slider(
+                0.5..=20.,
+                self.marquee_speed,
+                Message::SetMarqueeSpeed,
+            )
+            .step(0.5);
+            let gap = w::text(format!("gap {}", self.marquee_gap)).width(80.);
+            let gap_slider =
+                w::slider(0. ..=24., self.marquee_gap as f32, |v| {
+                    Message::SetMarqueeGap(v as usize)
+                });
+            w::row!(toggle, speed, speed_slider, gap, gap_slider).spacing(4.)
+        };
+
+        let timer = {
+            let toggle = w::checkbox("Timer", self.show_timer)
+                .on_toggle(Message::SetTimer);
+            if self.show_timer {
+                let mode = w::pick_list(
+                    TimerMode::ALL,
+                    Some(self.timer.mode),
+                    Message::SetTimerMode,
+                );
+                let mut row = w::row!(toggle, mode)
+                    .spacing(4.)
+                    .align_items(iced::Alignment::Center);
+                if self.timer.mode == TimerMode::Countdown {
+                    let secs = self.timer.duration.as_secs();
+                    let duration =
+                        w::text(format!("{:02}:{:02}", secs / 60, secs % 60))
+                            .width(80.);
+                    let slider = w::slider(10. ..=3600., secs as f32, |v| {
+                        Message::SetTimerDuration(Duration::from_secs(v as u64))
+                    })
+                    .step(10.);
+                    row = row.push(duration).push(slider);
+                }
+                let start_pause = if self.timer.is_running() {
+                    w::button("Pause").on_press(Message::PauseTimer)
+                } else {
+                    w::button("Start").on_press(Message::StartTimer)
+                };
+                let reset = w::button("Reset").on_press(Message::ResetTimer);
+                row.push(start_pause).push(reset)
+            } else {
+                w::row!(toggle)
+            }
+        };
+
+        let typewriter = {
+            let toggle = w::checkbox("Typewriter", self.typewriter)
+                .on_toggle(Message::SetTypewriter);
+            let speed = w::text(format!("{:.1} ch/s", self.typewriter_speed))
+                .width(80.);
+            let speed_slider = w::slider(
+                0.5..=20.,
+                self.typewriter_speed,
+                Message::SetTypewriterSpeed,
+            )
+            .step(0.5);
+            let repeat = w::checkbox("Loop", self.typewriter_loop)
+                .on_toggle(Message::SetTypewriterLoop);
+            w::row!(toggle, speed, speed_slider, repeat).spacing(4.)
+        };
+
+        let editor = {
+            let toggle = w::checkbox("Glyph editor", self.editor)
+                .on_toggle(Message::SetEditor);
+            if self.editor {
+                let ch =
+                    w::text_input("Character", &self.editor_char.to_string())
+                        .on_input(Message::SetEditorChar)
+                        .width(80.);
+                let glyph = self.editor_display.instantiate_editor(
+                    self.fonts[self.font]
+                        .1
+                        .get(&self.editor_char)
+                        .cloned()
+                        .unwrap_or_default(),
+                    Message::EditGlyph,
+                );
+                let missing = self.fonts[self.font].1.missing_ascii();
+                let missing = w::column!(
+                    w::text(format!("{} missing glyphs", missing.len())),
+                    w::scrollable(
+                        w::row(missing.into_iter().map(|ch| {
+                            w::button(w::text(ch))
+                                .on_press(Message::SetEditorChar(ch.into()))
+                                .into()
+                        }))
+                        .spacing(4.),
+                    )
+                    .direction(
+                        scrollable::Direction::Horizontal(
+                            scrollable::Properties::default(),
+                        )
+                    ),
+                )
+                .spacing(4.);
+                let save = w::button("Save font").on_press(Message::SaveFont);
+                w::row!(
+                    w::column!(toggle, w::row!(ch, save).spacing(4.), missing)
+                        .spacing(4.),
+                    glyph
+                )
+                .spacing(16.)
+            } else {
+                w::row!(toggle)
+            }
+        };
+
+        let font = {
+            let choices: Vec<_> = self
+                .fonts
+                .iter()
+                .enumerate()
+                .map(|(index, (name, _))| FontChoice {
+                    index,
+                    name: name.clone(),
+                })
+                .collect();
+            let selected = choices[self.font].clone();
+            let list =
+                w::pick_list(choices.clone(), Some(selected), |choice| {
+                    Message::SelectFont(choice.index)
+                });
+            let fallback = w::pick_list(
+                FallbackGlyph::ALL,
+                Some(FallbackGlyph::of(&self.fonts[self.font].1)),
+                Message::SetFallbackGlyph,
+            );
+            let fallback_choices: Vec<_> =
+                std::iter::once(FallbackFontChoice(None))
+                    .chain(
+                        choices
+                            .iter()
+                            .filter(|choice| choice.index != self.font)
+                            .cloned()
+                            .map(Some)
+                            .map(FallbackFontChoice),
+                    )
+                    .collect();
+            let fallback_font =
+                self.fallback_fonts.get(&self.fonts[self.font].0);
+            let selected_fallback = fallback_choices
+                .iter()
+                .find(|choice| {
+                    choice.0.as_ref().map(|choice| &choice.name)
+                        == fallback_font
+                })
+                .cloned();
+            let fallback_font =
+                w::pick_list(fallback_choices, selected_fallback, |choice| {
+                    Message::SetFallbackFont(choice.0.map(|choice| choice.name))
+                });
+            let kind = w::pick_list(
+                segments::DisplayKind::ALL,
+                Some(self.digit_display.options().kind),
+                Message::SetDisplayKind,
+            );
+            let row_choices: Vec<_> = std::iter::once(RowFontChoice(None))
+                .chain(choices.iter().cloned().map(Some).map(RowFontChoice))
+                .collect();
+            let color_choices: Vec<_> = std::iter::once(RowColorChoice(None))
+                .chain(
+                    markup::COLOR_NAMES
+                        .into_iter()
+                        .map(|named| RowColorChoice(Some(named))),
+                )
+                .collect();
+            let rows = (0..self.rows).map(|row| {
+                let font = self.row_fonts.get(row).and_then(Option::as_deref);
+                let selected = row_choices
+                    .iter()
+                    .find(|choice| {
+                        choice.0.as_ref().map(|choice| choice.name.as_str())
+                            == font
+                    })
+                    .cloned();
+                let color = self.row_color(row);
+                let selected_color = color_choices
+                    .iter()
+                    .find(|choice| choice.0.map(|(_, color)| color) == color)
+                    .copied();
+                w::row!(
+                    w::text(format!("Row {}", row + 1)),
+                    w::pick_list(
+                        row_choices.clone(),
+                        selected,
+                        move |choice| {
+                            Message::SetRowFont(
+                                row,
+                                choice.0.map(|choice| choice.name),
+                            )
+                        }
+                    ),
+                    w::pick_list(
+                        color_choices.clone(),
+                        selected_color,
+                        move |choice| {
+                            Message::SetRowColor(
+                                row,
+                                choice.0.map(|(_, color)| color),
+                            )
+                        }
+                    )
+                )
+                .spacing(4.)
+                .align_items(iced::Alignment::Center)
+                .into()
+            });
+            w::column!(
+                w::row!(
+                    w::text("Font").width(80.),
+                    list,
+                    kind,
+                    w::text("Unknown characters"),
+                    fallback,
+                    w::text("Missing glyphs from"),
+                    fallback_font
+                )
+                .spacing(4.),
+                w::scrollable(w::row(rows).spacing(16.)).direction(
+                    scrollable::Direction::Horizontal(
+                        scrollable::Properties::default(),
+                    )
+                )
+            )
+            .spacing(4.)
+        };
+
+        let input = {
+            let clock =
+                w::checkbox("Clock", self.clock).on_toggle(Message::SetClock);
+            let rtl = w::checkbox("Right to left", self.rtl)
+                .on_toggle(Message::SetRtl);
+            if self.clock {
+                let seconds = w::checkbox("Seconds", self.clock_seconds)
+                    .on_toggle(Message::SetClockSeconds);
+                let colons = w::checkbox("Colon cells", self.clock_colons)
+                    .on_toggle(Message::SetClockColons);
+                w::column!(
+                    w::row!(clock, rtl).spacing(16.),
+                    w::row!(seconds, colons).spacing(16.)
+                )
+                .spacing(4.)
+            } else {
+                let editor = w::text_editor(&self.text)
+                    .on_action(Message::TextAreaAction);
+                let merge = w::checkbox(
+                    "Periods as decimal points",
+                    self.merge_decimal_points,
+                )
+                .on_toggle(Message::SetMergeDecimalPoints);
+                let clear =
+                    w::button("Clear (Ctrl+L)").on_press(Message::ClearText);
+                let tab_width = w::pick_list(
+                    TAB_WIDTHS,
+                    Some(self.tab_width),
+                    Message::SetTabWidth,
+                );
+                let control = w::checkbox(
+                    format!(
+                        "Show control characters as {}",
+                        markup::CONTROL_PLACEHOLDER
+                    ),
+                    self.show_control_chars,
+                )
+                .on_toggle(Message::SetShowControlChars);
+                let wrap = w::checkbox("Word wrap", self.word_wrap)
+                    .on_toggle(Message::SetWordWrap);
+                let clipped =
+                    w::checkbox("Mark clipped text", self.mark_clipped)
+                        .on_toggle(Message::SetMarkClipped);
+                let mut input = w::column!(
+                    w::row!(clock, rtl, merge, clear).spacing(16.),
+                    w::row!(
+                        w::row!(w::text("Tab width"), tab_width)
+                            .spacing(4.)
+                            .align_items(iced::Alignment::Center),
+                        control,
+                        wrap,
+                        clipped
+                    )
+                    .spacing(16.)
+                    .align_items(iced::Alignment::Center),
+                    editor
+                )
+                .spacing(4.);
+                if let Some(ch) = self.cursor_char {
+                    let font = &self.fonts[self.font].1;
+                    let glyph = font.glyph(&ch);
+                    let source = if font.get_with_fallback(&ch).is_some() {
+                        ""
+                    } else {
+                        " (unknown character)"
+                    };
+                    input = input.push(
+                        w::text(format!(
+                            "{ch:?}{source}: {glyph} = {:#07x}",
+                            u32::from(glyph)
+                        ))
+                        .size(12.),
+                    );
+                }
+                let overflow =
+                    self.wrapped_rows().len().saturating_sub(self.rows);
+                if overflow > 0 {
+                    input = input.push(
+                        w::text(format!(
+                            "{overflow} more row(s) do not fit on the board"
+                        ))
+                        .size(12.),
+                    );
+                }
+                if let Some(path) = &self.watch {
+                    let status = match &self.watch_error {
+                        Some(err) => format!("{}: {err}", path.display()),
+                        None => format!("Watching {}", path.display()),
+                    };
+                    input = input.push(w::text(status).size(12.));
+                }
+                input
+            }
+        };
+
+        let damage = {
+            let toggle = w::checkbox("Damaged segments", self.damage_editor)
+                .on_toggle(Message::SetDamageEditor);
+            if self.damage_editor {
+                let dead = self.editor_display.instantiate_editor(
+                    self.digit_display.dead_segments(),
+                    Message::SetDeadSegments,
+                );
+                let stuck = self.editor_display.instantiate_editor(
+                    self.digit_display.stuck_segments(),
+                    Message::SetStuckSegments,
+                );
+                w::row!(
+                    w::container(toggle).width(160.),
+                    w::column!(w::text("Dead"), dead).spacing(4.),
+                    w::column!(w::text("Stuck"), stuck).spacing(4.)
+                )
+                .spacing(16.)
+            } else {
+                w::row!(toggle)
+            }
+        };
+
+        let raw = {
+            let input = w::text_input("A1,B,G1 or 0x1ffff", &self.raw_segments)
+                .on_input(Message::SetRawSegments)
+                .width(200.);
+            let mut raw = w::row!(w::text("Raw segments").width(80.), input)
+                .spacing(4.)
+                .align_items(iced::Alignment::Center);
+            if !self.raw_segments.trim().is_empty() {
+                raw = match parse_raw_segments(&self.raw_segments) {
+                    Ok(segments) => raw
+                        .push(self.digit_display.instantiate(segments))
+                        .push(w::text(format!(
+                            "{segments} = {:#07x}",
+                            u32::from(segments)
+                        ))),
+                    Err(err) => raw.push(w::text(err)),
+                };
+            }
+            raw
+        };
+
+        let test_pattern = {
+            let list = w::pick_list(
+                TestPattern::ALL,
+                Some(self.test_pattern),
+                Message::SetTestPattern,
+            );
+            w::row!(w::text("Test").width(80.), list).spacing(4.)
+        };
+
+        let export = {
+            let svg = w::button("Export SVG").on_press(Message::ExportSvg);
+            let ascii = w::button("Copy as text").on_press(Message::CopyAscii);
+            let gif = w::button("Record GIF").on_press(Message::RecordGif);
+            let frames = w::slider(1. ..=200., self.gif_frames as f32, |v| {
+                Message::SetGifFrames(v as usize)
+            });
+            let fps =
+                w::slider(1. ..=50., self.gif_fps, Message::SetGifFps).step(1.);
+            w::row!(
+                svg,
+                ascii,
+                gif,
+                w::text(format!("{} frames", self.gif_frames)),
+                frames,
+                w::text(format!("{} fps", self.gif_fps)),
+                fps
+            )
+            .spacing(4.)
+        };
+
+        let serial = {
+            let toggle =
+                w::checkbox("Stream to serial port", self.serial.is_some())
+                    .on_toggle(Message::SetSerialOutput);
+            let path = w::text_input("Serial port", &self.serial_path)
+                .on_input(Message::SetSerialPath)
+                .width(200.);
+            w::row!(toggle, path).spacing(16.)
+        };
+
+        let remote = w::checkbox(
+            format!("Accept text on TCP port {}", crate::remote::PORT),
+            self.remote,
+        )
+        .on_toggle(Message::SetRemote);
 
         // w::text(format!("{:#?}", self.digit))
-        w::container(w::column!(thickness, gap, input, display).spacing(16.))
-            .padding(16.)
-            .into()
+        w::container(
+            w::column!(
+                board_size,
+                spacing,
+                zoom,
+                size,
+                thickness,
+                gap,
+                slant,
+                aspect,
+                profile,
+                display_theme,
+                color,
+                render_mode,
+                glow,
+                transition,
+                flicker,
+                background,
+                blink,
+                marquee,
+                typewriter,
+                timer,
+                font,
+                editor,
+                damage,
+                raw,
+                test_pattern,
+                input,
+                export,
+                serial,
+                remote,
+                display
+            )
+            .spacing(16.),
+        )
+        .padding(WINDOW_PADDING)
+        .into()
+    }
+}
+
+/// Rows of a board with `rows` rows spaced by `row_height` which intersect
+/// the region from `top` to `bottom`, measured from the top of the first row.
+fn rows_between(
+    top: f32,
+    bottom: f32,
+    row_height: f32,
+    rows: usize,
+) -> std::ops::Range<usize> {
+    let first = (top / row_height).floor().max(0.) as usize;
+    let last = (bottom / row_height).ceil().max(0.) as usize;
+    first.min(rows)..last.min(rows)
+}
+
+/// Parses segment names like `A1,B,G1` or a hex value prefixed with `0x`.
+fn parse_raw_segments(raw: &str) -> Result<SegmentBits, String> {
+    let raw = raw.trim();
+    match raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        Some(hex) => {
+            let bits = u32::from_str_radix(hex, 16)
+                .map_err(|err| format!("invalid hex value: {err}"))?;
+            if bits & !u32::from(SegmentBits::all()) != 0 {
+                return Err(format!(
+                    "only the lowest {} bits are segments",
+                    segments::SEGMENT_COUNT
+                ));
+            }
+            Ok(bits.into())
+        }
+        None => raw.parse::<SegmentBits>().map_err(|err| err.to_string()),
+    }
+}
+
+/// Color of a solid style, or of the first stop of a gradient.
+fn top_color(style: &Style) -> Color {
+    match style {
+        Style::Solid(color) => *color,
+        Style::Gradient(Gradient::Linear(linear)) => {
+            linear.stops[0].map_or(Color::WHITE, |stop| stop.color)
+        }
+    }
+}
+
+/// Darker shade of `color` for the end of a fade.
+fn dim(color: Color) -> Color {
+    Color::from_rgba(color.r * 0.25, color.g * 0.25, color.b * 0.25, color.a)
+}
+
+fn color_sliders<'a>(
+    color: Color,
+    on_change: fn(Color) -> Message,
+) -> iced::widget::Column<'a, Message> {
+    use iced::widget as w;
+
+    let channel = |label: &str, value: f32, set: fn(&mut Color, f32)| {
+        let display =
+            w::text(format!("{label} {:.0}", value * 255.)).width(80.);
+        let slider = w::slider(0. ..=1., value, move |v| {
+            let mut color = color;
+            set(&mut color, v);
+            on_change(color)
+        })
+        .step(1. / 255.);
+        w::row!(display, slider).spacing(4.)
+    };
+
+    w::column!(
+        channel("R", color.r, |c, v| c.r = v),
+        channel("G", color.g, |c, v| c.g = v),
+        channel("B", color.b, |c, v| c.b = v),
+    )
+    .spacing(4.)
+}
+
+/// The built-in font, followed by the fonts found in [`SEGMENTED_FONTS_DIR`].
+fn load_segmented_fonts() -> Vec<(String, SegmentedFont)> {
+    let mut fonts = vec![
+        ("Default".into(), segments::segmented_font::DEFAULT.clone()),
+        (
+            "7-segment hex".into(),
+            segments::segmented_font::SEVEN_SEGMENT.clone(),
+        ),
+        (
+            "14-segment".into(),
+            segments::segmented_font::FOURTEEN_SEGMENT.clone(),
+        ),
+    ];
+
+    match SegmentedFont::load_dir(SEGMENTED_FONTS_DIR) {
+        Ok(loaded) => {
+            for (name, result) in loaded {
+                match result {
+                    Ok(font) => fonts.push((name, font)),
+                    Err(err) => {
+                        eprintln!("Failed to load segmented font {name}: {err}")
+                    }
+                }
+            }
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => eprintln!("Failed to read {SEGMENTED_FONTS_DIR}: {err}"),
+    }
+
+    fonts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_segments_from_names_or_hex() {
+        let bits = Segment::A1 | Segment::B | Segment::DP;
+        assert_eq!(parse_raw_segments("a1,B|dp"), Ok(bits));
+        assert_eq!(parse_raw_segments(" 0x10005 "), Ok(bits));
+        assert_eq!(parse_raw_segments("0X1FFFF"), Ok(SegmentBits::all()));
+        assert!(parse_raw_segments("0x20000").is_err());
+        assert!(parse_raw_segments("0xg").is_err());
+        assert!(parse_raw_segments("A1,Q").is_err());
+    }
+
+    #[test]
+    fn visible_rows_of_long_board() {
+        // Rows of 100 pixels seen through a viewport of 250 pixels, with 8
+        // pixels of padding above the first row
+        let rows =
+            |offset: f32| rows_between(offset - 8., offset + 242., 100., 100);
+        assert_eq!(rows(0.), 0..3);
+        assert_eq!(rows(158.), 1..4);
+        assert_eq!(rows(208.), 2..5);
+        assert_eq!(rows(9766.), 97..100);
+        // A viewport within the padding shows no rows
+        assert_eq!(rows_between(-8., -3., 100., 100), 0..0);
+        assert_eq!(rows_between(-8., 0.5, 100., 100), 0..1);
+    }
+
+    /// App with the default settings, without any commands run.
+    fn app() -> CatoDisplayApp {
+        CatoDisplayApp::new(Flags {
+            text: None,
+            stdin: false,
+            watch: None,
+            config: Config::default(),
+        })
+        .0
+    }
+
+    #[test]
+    fn edits_show_through_fallback_fonts() {
+        use crate::segments::segmented_font;
+
+        let mut app = app();
+        app.fonts = vec![
+            ("Base".into(), segmented_font!['A' => A1; 'B' => B]),
+            ("Top".into(), segmented_font!['A' => A2]),
+        ];
+        let _ = app.update(Message::SelectFont(1));
+        let _ = app.update(Message::SetFallbackFont(Some("Base".into())));
+        assert_eq!(app.fonts[1].1.glyph(&'B'), SegmentBits::new() | Segment::B);
+
+        let _ = app.update(Message::SelectFont(0));
+        let _ = app.update(Message::SetEditorChar("B".into()));
+        let _ = app.update(Message::EditGlyph(SegmentBits::new() | Segment::C));
+        assert_eq!(app.fonts[1].1.glyph(&'B'), SegmentBits::new() | Segment::C);
+        // Own glyphs still take precedence
+        let _ = app.update(Message::SetEditorChar("A".into()));
+        let _ =
+            app.update(Message::EditGlyph(SegmentBits::new() | Segment::D1));
+        assert_eq!(
+            app.fonts[1].1.glyph(&'A'),
+            SegmentBits::new() | Segment::A2
+        );
+
+        let _ = app.update(Message::SetEditor(true));
+        let _ = app.update(Message::UndoGlyphEdit);
+        let _ = app.update(Message::UndoGlyphEdit);
+        assert_eq!(app.fonts[1].1.glyph(&'B'), SegmentBits::new() | Segment::B);
+
+        // Fonts falling back on each other end the chain
+        let _ = app.update(Message::SetFallbackFont(Some("Top".into())));
+        assert_eq!(
+            app.fonts[0].1.glyph(&'A'),
+            SegmentBits::new() | Segment::A1
+        );
+        assert_eq!(app.fonts[1].1.glyph(&'B'), SegmentBits::new() | Segment::B);
+        assert_eq!(app.fonts[1].1.glyph(&'Z'), SegmentBits::new());
+    }
+
+    #[test]
+    fn remote_lines_keep_markup_and_overflow() {
+        let mut app = app();
+        app.rows = 2;
+        app.cols = 8;
+        for line in ["one", "{red}WARNING{/}", "a line longer than the board"] {
+            let _ = app.update(Message::RemoteText(line.into()));
+        }
+        assert_eq!(
+            app.text.text().trim_end(),
+            "{red}WARNING{/}\na line longer than the board"
+        );
+        let rows = app.text_rows();
+        assert_eq!(rows[0].len(), 7);
+        assert!(rows[0].iter().all(|cell| cell.color.is_some()));
+        assert_eq!(rows[1].len(), 28);
     }
 }
@@ -0,0 +1,125 @@
+//! Settings kept between runs in `config.toml` in the user's config
+//! directory.
+
+use std::{fs, io, path::PathBuf};
+
+use iced::{Color, Theme};
+use serde::{Deserialize, Serialize};
+
+use crate::segments::{serde_iced, DigitOptions};
+
+const FILE_NAME: &str = "config.toml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Size of the window when it is not full screen
+    pub window_size: [f32; 2],
+    /// Position of the window when it is not full screen, or `None` to let
+    /// the platform place it
+    pub window_position: Option<[f32; 2]>,
+    /// Show only the board, covering the whole screen
+    pub fullscreen: bool,
+    /// Theme of the controls around the board
+    #[serde(with = "serde_iced::theme")]
+    pub theme: Theme,
+    #[serde(with = "serde_iced::color")]
+    pub background: Color,
+    pub char_spacing: f32,
+    pub line_spacing: f32,
+    pub digit: DigitOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window_size: [800., 600.],
+            window_position: None,
+            fullscreen: false,
+            theme: Theme::TokyoNight,
+            background: Color::BLACK,
+            char_spacing: crate::app::CHAR_SPACING,
+            line_spacing: crate::app::LINE_SPACING,
+            digit: DigitOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// No config directory is known for this platform.
+    NoConfigDir,
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoConfigDir => f.write_str("no config directory available"),
+            Self::Io(err) => err.fmt(f),
+            Self::Parse(err) => write!(f, "invalid config: {err}"),
+            Self::Write(err) => write!(f, "failed to write config: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Directory of the config file and other settings.
+pub fn dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "cato-display-app")
+        .map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Location of the config file.
+pub fn path() -> Option<PathBuf> {
+    dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl Config {
+    /// Reads the config file, writing the defaults on the first run. Falls
+    /// back to the defaults with a warning if the file cannot be read.
+    pub fn load_or_default() -> Self {
+        match Self::load() {
+            Ok(Some(config)) => config,
+            Ok(None) => {
+                let config = Self::default();
+                if let Err(err) = config.save() {
+                    eprintln!("Failed to write the default config: {err}");
+                }
+                config
+            }
+            Err(err) => {
+                eprintln!("Using the default config: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Reads the config file, or `None` if there is none yet.
+    pub fn load() -> Result<Option<Self>, ConfigError> {
+        let path = path().ok_or(ConfigError::NoConfigDir)?;
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(None)
+            }
+            Err(err) => return Err(ConfigError::Io(err)),
+        };
+        toml::from_str(&source)
+            .map(Some)
+            .map_err(ConfigError::Parse)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = path().ok_or(ConfigError::NoConfigDir)?;
+        let source =
+            toml::to_string_pretty(self).map_err(ConfigError::Write)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(ConfigError::Io)?;
+        }
+        fs::write(path, source).map_err(ConfigError::Io)
+    }
+}
@@ -1,3 +1,5 @@
+use std::{fs, io, path::Path};
+
 use iced::Command;
 
 macro_rules! fonts {
@@ -13,8 +15,51 @@ const FONTS: &[(&str, &[u8])] = fonts![
 
 pub const NUM_FONTS: usize = FONTS.len();
 
+/// Directory of additional label fonts loaded at runtime.
+pub const FONTS_DIR: &str = "label_fonts";
+
 pub fn load_fonts() -> Command<crate::app::Message> {
     Command::batch(FONTS.iter().map(|(name, bytes)| {
-        iced::font::load(*bytes).map(|result| crate::app::Message::FontLoaded { name, result })
+        iced::font::load(*bytes).map(|result| crate::app::Message::FontLoaded {
+            name: name.to_string(),
+            result,
+        })
     }))
 }
+
+/// Reads every `.ttf` file in `dir` and loads it, skipping files which
+/// cannot be read. Returns the number of fonts that will be loaded.
+pub fn load_fonts_from_dir(
+    dir: impl AsRef<Path>,
+) -> io::Result<(usize, Command<crate::app::Message>)> {
+    let mut fonts = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "ttf") {
+            continue;
+        }
+        let Some(name) = path.file_stem() else {
+            continue;
+        };
+        // One broken file should not keep the other fonts from loading
+        match fs::read(&path) {
+            Ok(bytes) => {
+                fonts.push((name.to_string_lossy().into_owned(), bytes));
+            }
+            Err(err) => {
+                eprintln!("Skipping the font {}: {err}", path.display());
+            }
+        }
+    }
+
+    let count = fonts.len();
+    let command = Command::batch(fonts.into_iter().map(|(name, bytes)| {
+        iced::font::load(bytes).map(move |result| {
+            crate::app::Message::FontLoaded {
+                name: name.clone(),
+                result,
+            }
+        })
+    }));
+    Ok((count, command))
+}
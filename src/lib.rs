@@ -0,0 +1,11 @@
+pub mod app;
+pub mod config;
+pub mod fonts;
+pub mod markup;
+pub mod profiles;
+pub mod remote;
+pub mod segments;
+pub mod serial;
+pub mod stdin;
+pub mod timer;
+pub mod watch;
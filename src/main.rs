@@ -1,16 +1,88 @@
-use iced::{Application, Size};
-
-pub mod app;
-pub mod fonts;
-pub mod segments;
+use cato_display_app::{app, config, markup, segments};
+use iced::{window::Position, Application, Point, Size};
 
 fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        if let Err(err) = headless(&args) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let config = config::Config::load_or_default();
+    let [width, height] = config.window_size;
+    let position = match config.window_position {
+        Some([x, y]) => Position::Specific(Point::new(x, y)),
+        None => Position::Default,
+    };
     app::CatoDisplayApp::run(iced::Settings {
+        flags: app::Flags {
+            text: arg_value(&args, "--text").map(str::to_string),
+            stdin: args.iter().any(|arg| arg == "--stdin"),
+            watch: arg_value(&args, "--watch").map(Into::into),
+            config,
+        },
         default_font: iced::Font::with_name("Nunito"),
         window: iced::window::Settings {
-            size: Size::new(800., 600.),
+            size: Size::new(width, height),
+            position,
+            // The config is saved before closing
+            exit_on_close_request: false,
             ..Default::default()
         },
         ..Default::default()
     })
 }
+
+/// Renders `--text` with the default font and options into the PNG file
+/// given by `--out`, without opening a window.
+fn headless(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let text = arg_value(args, "--text").unwrap_or_default();
+    let out = arg_value(args, "--out").ok_or("missing `--out <file.png>`")?;
+
+    let font = &*segments::segmented_font::DEFAULT;
+    let lines: Vec<Vec<_>> = text
+        .lines()
+        .map(|line| {
+            markup::expand_control_chars(
+                line.chars().map(markup::Cell::plain).collect(),
+                markup::TAB_WIDTH,
+                true,
+            )
+        })
+        .collect();
+    let cols = lines.iter().map(Vec::len).max();
+    let board: Vec<Vec<_>> = lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|cell| cell.ch)
+                .chain(std::iter::repeat(' '))
+                .take(cols.unwrap_or(0))
+                .map(|ch| font.glyph(&ch).into())
+                .collect()
+        })
+        .collect();
+
+    let pixmap = segments::raster::rasterize(
+        &board,
+        &segments::DigitOptions::default(),
+        &segments::svg::SvgLayout {
+            char_spacing: app::CHAR_SPACING,
+            line_spacing: app::LINE_SPACING,
+            padding: app::BOARD_PADDING,
+            background: iced::Color::BLACK,
+        },
+    )
+    .ok_or("nothing to render")?;
+    pixmap.save_png(out)?;
+    Ok(())
+}
+
+/// Value following the flag `name` on the command line.
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.get(index + 1).map(String::as_str)
+}
@@ -0,0 +1,208 @@
+//! Inline color markup for the displayed text.
+//!
+//! `{red}WARN{/}` shows `WARN` in red. A tag names a color from
+//! [`COLOR_NAMES`] or gives one as `#rrggbb`, and `{/}` returns to the
+//! display color. Braces that do not form a known tag are shown as they are.
+
+use iced::Color;
+
+/// A character with the color it is shown in, if it differs from the
+/// display color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+    /// Light the decimal point in addition to the glyph of `ch`
+    pub dp: bool,
+}
+
+impl Cell {
+    pub const BLANK: Self = Self::plain(' ');
+
+    /// `ch` in the display color.
+    pub const fn plain(ch: char) -> Self {
+        Self {
+            ch,
+            color: None,
+            dp: false,
+        }
+    }
+}
+
+/// Columns between the tab stops of the displayed text by default.
+pub const TAB_WIDTH: usize = 4;
+/// Character shown in place of control characters other than tabs.
+pub const CONTROL_PLACEHOLDER: char = '?';
+
+pub const COLOR_NAMES: [(&str, Color); 10] = [
+    ("red", Color::from_rgb(1., 0., 0.)),
+    ("orange", Color::from_rgb(1., 0.5, 0.)),
+    ("amber", Color::from_rgb(1., 0.69, 0.)),
+    ("yellow", Color::from_rgb(1., 1., 0.)),
+    ("green", Color::from_rgb(0., 1., 0.)),
+    ("cyan", Color::from_rgb(0., 1., 1.)),
+    ("blue", Color::from_rgb(0.2, 0.4, 1.)),
+    ("magenta", Color::from_rgb(1., 0., 1.)),
+    ("white", Color::WHITE),
+    ("gray", Color::from_rgb(0.5, 0.5, 0.5)),
+];
+
+/// Splits a line into its characters and their colors, removing the tags.
+pub fn parse_line(line: &str) -> Vec<Cell> {
+    let mut cells = Vec::with_capacity(line.len());
+    let mut color = None;
+    let mut rest = line;
+
+    while let Some(ch) = rest.chars().next() {
+        if ch == '{' {
+            if let Some((tag, after)) = rest[1..].split_once('}') {
+                if let Some(tag_color) = parse_tag(tag) {
+                    color = tag_color;
+                    rest = after;
+                    continue;
+                }
+            }
+        }
+        cells.push(Cell {
+            ch,
+            color,
+            dp: false,
+        });
+        rest = &rest[ch.len_utf8()..];
+    }
+
+    cells
+}
+
+/// Parses the inside of a tag into the color it sets, where `Some(None)`
+/// resets to the display color.
+fn parse_tag(tag: &str) -> Option<Option<Color>> {
+    if tag == "/" {
+        return Some(None);
+    }
+    if let Some(hex) = tag.strip_prefix('#') {
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        let [_, r, g, b] = rgb.to_be_bytes();
+        return Some(Some(Color::from_rgb8(r, g, b)));
+    }
+    COLOR_NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+        .map(|&(_, color)| Some(color))
+}
+
+/// Shows every `.` directly following a digit as the decimal point of that
+/// digit instead of in a cell of its own, like `3.14` on a calculator. Of
+/// several consecutive periods only the first is merged, and a period
+/// without a digit before it keeps its cell.
+pub fn merge_decimal_points(cells: Vec<Cell>) -> Vec<Cell> {
+    let mut merged: Vec<Cell> = Vec::with_capacity(cells.len());
+    for cell in cells {
+        if let Some(prev) = merged.last_mut() {
+            if cell.ch == '.' && prev.ch.is_ascii_digit() && !prev.dp {
+                prev.dp = true;
+                continue;
+            }
+        }
+        merged.push(cell);
+    }
+    merged
+}
+
+/// Expands tabs to spaces up to the next multiple of `tab_width` columns.
+/// Other control characters are shown as [`CONTROL_PLACEHOLDER`], or
+/// removed if `show_control` is unset.
+pub fn expand_control_chars(
+    cells: Vec<Cell>,
+    tab_width: usize,
+    show_control: bool,
+) -> Vec<Cell> {
+    let tab_width = tab_width.max(1);
+    let mut expanded = Vec::with_capacity(cells.len());
+    for cell in cells {
+        match cell.ch {
+            '\t' => {
+                let spaces = tab_width - expanded.len() % tab_width;
+                let space = Cell { ch: ' ', ..cell };
+                expanded.extend(std::iter::repeat_n(space, spaces));
+            }
+            ch if ch.is_control() => {
+                if show_control {
+                    expanded.push(Cell {
+                        ch: CONTROL_PLACEHOLDER,
+                        ..cell
+                    });
+                }
+            }
+            _ => expanded.push(cell),
+        }
+    }
+    expanded
+}
+
+/// Breaks `cells` into rows of at most `width` cells at the spaces between
+/// words. Words longer than a row are broken anywhere, and the spaces at a
+/// break are removed.
+pub fn wrap(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+    let is_space = |cell: &Cell| cell.ch == ' ';
+    if width == 0 {
+        return vec![cells.to_vec()];
+    }
+
+    let mut rows = Vec::new();
+    let mut rest = cells;
+    while rest.len() > width {
+        // Leading spaces only remain on the first row, where they indent it
+        let indent = rest.iter().take_while(|cell| is_space(cell)).count();
+        let space = rest[..=width]
+            .iter()
+            .skip(indent)
+            .rposition(is_space)
+            .map(|space| space + indent);
+        let (row, next) = match space {
+            Some(space) => (&rest[..space], &rest[space..]),
+            None => rest.split_at(width),
+        };
+        let trailing = row.iter().rev().take_while(|cell| is_space(cell));
+        rows.push(row[..row.len() - trailing.count()].to_vec());
+        let spaces = next.iter().take_while(|cell| is_space(cell)).count();
+        rest = &next[spaces..];
+    }
+    // Spaces at the end of the line do not start a row of their own
+    if !rest.is_empty() || rows.is_empty() {
+        rows.push(rest.to_vec());
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(text: &str, tab_width: usize, show_control: bool) -> String {
+        let cells = text.chars().map(Cell::plain).collect();
+        expand_control_chars(cells, tab_width, show_control)
+            .into_iter()
+            .map(|cell| cell.ch)
+            .collect()
+    }
+
+    #[test]
+    fn expands_tabs_to_tab_stops() {
+        assert_eq!(expand("a\tb", TAB_WIDTH, true), "a   b");
+        assert_eq!(expand("a\tb", 2, true), "a b");
+        assert_eq!(expand("ab\tc", 2, true), "ab  c");
+        assert_eq!(expand("\tb", 8, true), "        b");
+        // A width of 0 is treated as 1
+        assert_eq!(expand("a\tb", 0, true), "a b");
+    }
+
+    #[test]
+    fn maps_control_chars() {
+        assert_eq!(expand("a\u{7}b\rc", TAB_WIDTH, true), "a?b?c");
+        assert_eq!(expand("a\u{7}b\rc", TAB_WIDTH, false), "abc");
+    }
+}
@@ -0,0 +1,110 @@
+//! Named looks of the display which can be switched between, kept in
+//! `profiles.toml` in the user's config directory.
+
+use std::{collections::BTreeMap, fs, io};
+
+use iced::{widget::canvas::Style, Color};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, ConfigError};
+use crate::segments::{serde_iced, DigitOptions, DisplayKind};
+
+const FILE_NAME: &str = "profiles.toml";
+/// Profile which always exists and cannot be renamed or deleted.
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// Options, colors and spacing of the display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(with = "serde_iced::color")]
+    pub background: Color,
+    pub char_spacing: f32,
+    pub line_spacing: f32,
+    pub digit: DigitOptions,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            background: Color::BLACK,
+            char_spacing: crate::app::CHAR_SPACING,
+            line_spacing: crate::app::LINE_SPACING,
+            digit: DigitOptions::default(),
+        }
+    }
+}
+
+/// Profiles by their names.
+pub type Profiles = BTreeMap<String, Profile>;
+
+/// Profiles available before the user saved any.
+pub fn presets() -> Profiles {
+    let billboard = Profile {
+        background: Color::from_rgb(0.06, 0.03, 0.),
+        char_spacing: 12.,
+        digit: DigitOptions {
+            on_fill: Style::Solid(Color::from_rgb(1., 0.69, 0.)),
+            thickness: 7.,
+            glow: true,
+            zoom: 1.5,
+            ..DigitOptions::default()
+        },
+        ..Profile::default()
+    };
+    let clock = Profile {
+        background: Color::from_rgb(0., 0.05, 0.02),
+        digit: DigitOptions {
+            slant: 0.1,
+            kind: DisplayKind::SevenSegment,
+            ..DigitOptions::with_colors(
+                Color::from_rgb(0.2, 1., 0.5),
+                Color::from_rgb(0.02, 0.1, 0.05),
+            )
+        },
+        ..Profile::default()
+    };
+    let retro = Profile {
+        digit: DigitOptions {
+            kind: DisplayKind::FourteenSegment,
+            corner_radius: 2.,
+            ..DigitOptions::with_colors(
+                Color::from_rgb(1., 0., 0.),
+                Color::from_rgb(0.12, 0., 0.),
+            )
+        },
+        ..Profile::default()
+    };
+    [
+        (DEFAULT_PROFILE, Profile::default()),
+        ("Billboard", billboard),
+        ("Clock", clock),
+        ("Retro", retro),
+    ]
+    .into_iter()
+    .map(|(name, profile)| (name.to_string(), profile))
+    .collect()
+}
+
+/// Reads the saved profiles, or the [`presets`] if none were saved yet.
+/// The [default profile](DEFAULT_PROFILE) is always included.
+pub fn load() -> Result<Profiles, ConfigError> {
+    let path = config::dir()
+        .ok_or(ConfigError::NoConfigDir)?
+        .join(FILE_NAME);
+    let mut profiles: Profiles = match fs::read_to_string(path) {
+        Ok(source) => toml::from_str(&source).map_err(ConfigError::Parse)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => presets(),
+        Err(err) => return Err(ConfigError::Io(err)),
+    };
+    profiles.entry(DEFAULT_PROFILE.to_string()).or_default();
+    Ok(profiles)
+}
+
+pub fn save(profiles: &Profiles) -> Result<(), ConfigError> {
+    let dir = config::dir().ok_or(ConfigError::NoConfigDir)?;
+    let source =
+        toml::to_string_pretty(profiles).map_err(ConfigError::Write)?;
+    fs::create_dir_all(&dir).map_err(ConfigError::Io)?;
+    fs::write(dir.join(FILE_NAME), source).map_err(ConfigError::Io)
+}
@@ -0,0 +1,121 @@
+//! Lets other programs change the displayed text over TCP.
+//!
+//! Clients connect to [`PORT`] and send UTF-8 text separated by `\n`. Every
+//! line is appended to the display as a new row, scrolling older rows out once
+//! all rows are used. Lines longer than [`MAX_LINE_LEN`] bytes are cut off.
+//! Nothing is sent back to the client.
+
+use iced::futures::{Stream, StreamExt};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader},
+    net::TcpListener,
+};
+
+use crate::app::Message;
+
+pub const PORT: u16 = 7878;
+
+/// Longest line in bytes kept from a client, so that a client never sending
+/// a line break cannot use up the memory. The rest of the line is skipped.
+pub const MAX_LINE_LEN: u64 = 64 * 1024;
+
+/// Listens for clients as long as the subscription is active.
+pub fn listen(port: u16) -> iced::Subscription<Message> {
+    struct Listener;
+
+    iced::subscription::channel(
+        (std::any::TypeId::of::<Listener>(), port),
+        64,
+        move |output| async move {
+            match TcpListener::bind(("0.0.0.0", port)).await {
+                Ok(listener) => loop {
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            tokio::spawn(
+                                lines(stream)
+                                    .map(|line| Ok(Message::RemoteText(line)))
+                                    .forward(output.clone()),
+                            );
+                        }
+                        Err(err) => eprintln!("Failed to accept client: {err}"),
+                    }
+                },
+                Err(err) => {
+                    eprintln!("Failed to listen on port {port}: {err}");
+                    std::future::pending().await
+                }
+            }
+        },
+    )
+}
+
+/// Lines sent by a client, without the line breaks and cut to
+/// [`MAX_LINE_LEN`] bytes. Invalid UTF-8 is replaced.
+fn lines(stream: impl AsyncRead + Unpin) -> impl Stream<Item = String> {
+    iced::futures::stream::unfold(
+        BufReader::new(stream),
+        |mut reader| async move {
+            match read_line(&mut reader).await {
+                Ok(Some(line)) => Some((line, reader)),
+                Ok(None) => None,
+                Err(err) => {
+                    eprintln!("Failed to read from client: {err}");
+                    None
+                }
+            }
+        },
+    )
+}
+
+/// Reads the next line, or `None` once the client has disconnected.
+async fn read_line(
+    reader: &mut (impl AsyncBufReadExt + Unpin),
+) -> std::io::Result<Option<String>> {
+    let mut line = Vec::new();
+    if (&mut *reader)
+        .take(MAX_LINE_LEN)
+        .read_until(b'\n', &mut line)
+        .await?
+        == 0
+    {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    } else {
+        // Skip the rest of a long line
+        let mut rest = Vec::new();
+        while (&mut *reader)
+            .take(MAX_LINE_LEN)
+            .read_until(b'\n', &mut rest)
+            .await?
+            != 0
+            && rest.pop() != Some(b'\n')
+        {
+            rest.clear();
+        }
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::futures::executor;
+
+    use super::*;
+
+    #[test]
+    fn cuts_long_lines() {
+        let long = "x".repeat(MAX_LINE_LEN as usize * 2 + 1);
+        let input = format!("first\r\n{long}\nlast");
+        let lines: Vec<String> =
+            executor::block_on(lines(input.as_bytes()).collect());
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "first");
+        assert_eq!(lines[1].len(), MAX_LINE_LEN as usize);
+        assert_eq!(lines[2], "last");
+    }
+}
@@ -0,0 +1,105 @@
+//! Plain text approximation of a board of digits, for pasting mockups where
+//! images are inconvenient.
+//!
+//! Every digit takes five lines, which look like this with all segments lit:
+//!
+//! ```text
+//!  - -
+//! |\|/|
+//!  - -
+//! |/|\|
+//!  - - .
+//! ```
+
+use super::{DisplayKind, Segment, SegmentBits, SEGMENT_COUNT};
+
+/// Width of a digit in characters, including the decimal point.
+const CELL_WIDTH: usize = 6;
+const CELL_HEIGHT: usize = 5;
+
+/// Line, column and character of every segment within a digit.
+const STROKES: [(Segment, usize, usize, char); SEGMENT_COUNT] = {
+    use Segment::*;
+    [
+        (A1, 0, 1, '-'),
+        (A2, 0, 3, '-'),
+        (F, 1, 0, '|'),
+        (H, 1, 1, '\\'),
+        (I, 1, 2, '|'),
+        (J, 1, 3, '/'),
+        (B, 1, 4, '|'),
+        (G1, 2, 1, '-'),
+        (G2, 2, 3, '-'),
+        (E, 3, 0, '|'),
+        (K, 3, 1, '/'),
+        (L, 3, 2, '|'),
+        (M, 3, 3, '\\'),
+        (C, 3, 4, '|'),
+        (D1, 4, 1, '-'),
+        (D2, 4, 3, '-'),
+        (DP, 4, 5, '.'),
+    ]
+};
+
+/// Draws the lit segments of every digit of the `board`, which is given as
+/// a list of rows. Digits are separated by a space and rows by an empty
+/// line, and trailing spaces are removed.
+pub fn board_to_ascii(board: &[Vec<SegmentBits>], kind: DisplayKind) -> String {
+    board
+        .iter()
+        .map(|row| {
+            let cells: Vec<_> =
+                row.iter().map(|&bits| cell_to_ascii(bits, kind)).collect();
+            (0..CELL_HEIGHT)
+                .map(|line| {
+                    let line: Vec<String> = cells
+                        .iter()
+                        .map(|cell| cell[line].iter().collect())
+                        .collect();
+                    line.join(" ").trim_end().to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Lines of a single digit.
+fn cell_to_ascii(
+    bits: SegmentBits,
+    kind: DisplayKind,
+) -> [[char; CELL_WIDTH]; CELL_HEIGHT] {
+    let mut cell = [[' '; CELL_WIDTH]; CELL_HEIGHT];
+    let available = kind.segments();
+    for (segment, line, col, ch) in STROKES {
+        if !bits.contains(segment) {
+            continue;
+        }
+        cell[line][col] = ch;
+        // Kinds without split bars draw the first half across the whole bar
+        let second_half = match segment {
+            Segment::A1 => Some(Segment::A2),
+            Segment::D1 => Some(Segment::D2),
+            Segment::G1 => Some(Segment::G2),
+            _ => None,
+        };
+        if second_half.is_some_and(|half| !available.contains(half)) {
+            cell[line][col + 1..=col + 2].fill(ch);
+        }
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segments::segmented_font::DEFAULT;
+
+    #[test]
+    fn draws_eight() {
+        let board = [vec![DEFAULT.glyph(&'8')]];
+        let ascii = board_to_ascii(&board, DisplayKind::SixteenSegment);
+        assert_eq!(ascii, " - -\n|   |\n - -\n|   |\n - -");
+    }
+}
@@ -1,26 +1,228 @@
-use std::ops::{BitAnd, BitOr};
+mod colon;
+mod flicker;
+pub mod geometry;
+mod mesh;
 
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not},
+    str::FromStr,
+    sync::{Arc, Mutex, Weak},
+    time::Instant,
+};
+
+use glam::Mat2;
 use iced::{
-    widget::canvas::{fill::Rule, Cache, Fill, Geometry, Path, Program},
-    Color, Length, Size, Vector,
+    widget::canvas::{
+        self, fill::Rule, Cache, Fill, Frame, Geometry, Gradient, Path,
+        Program, Stroke, Style,
+    },
+    Color, Length, Point, Size, Vector,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+use flicker::Flicker;
+pub use mesh::meshes_supported;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DigitOptions {
+    #[serde(with = "super::serde_iced::size")]
     pub size: Size<f32>,
+    /// Space between adjacent segments. Negative gaps fuse the segments,
+    /// and the gap is clamped to [`geometry::gap_range`] when drawing.
     pub gap: f32,
     pub thickness: f32,
     pub slant: f32,
-    pub fill: iced::widget::canvas::Style,
+    /// Horizontal scale of the glyph within its cell, below 1 for condensed
+    /// and above 1 for expanded glyphs. The thickness and the gaps are not
+    /// scaled. Expanded glyphs extend into the spacing between the digits.
+    pub aspect: f32,
+    pub corner_radius: f32,
+    /// Rotation of the whole digit around its center in radians.
+    pub rotation: f32,
+    /// Magnification of the digit and its canvas.
+    pub zoom: f32,
+    /// Flip the digits left to right, as seen in a mirror. Boards also
+    /// reverse the order of the digits in each row.
+    pub mirror_x: bool,
+    /// Turn the digits by 180°, for displays mounted upside down. Boards
+    /// also reverse the order of their rows and of the digits in each row.
+    pub upside_down: bool,
+    pub render_mode: RenderMode,
+    /// Width of the strokes in the outline render modes.
+    pub outline_width: f32,
+    /// Round the corners of the segments to whole pixels on screen. Edges
+    /// along the pixel grid stay sharp instead of being blurred by
+    /// anti-aliasing, but segment widths become uneven and slanted edges
+    /// look jagged at small sizes.
+    pub crisp: bool,
+    /// Fill all lit and all unlit segments as one path each instead of one
+    /// path per segment. The glyph editor always draws them separately.
+    pub batched: bool,
+    pub kind: DisplayKind,
+    /// Style of lit segments.
+    #[serde(with = "super::serde_iced::style")]
+    pub on_fill: iced::widget::canvas::Style,
+    /// Opacity of lit segments with a solid color, from 0 to 1.
+    pub brightness: f32,
+    /// Draw a faint halo behind lit segments.
+    pub glow: bool,
+    /// Opacity of the halo relative to the lit segments.
+    pub glow_intensity: f32,
+    /// Distance by which the halo extends past the segment outline.
+    pub glow_spread: f32,
+    /// Crossfade segments turning on or off instead of switching them
+    /// instantly. Only supported by [`RenderMode::Path`].
+    pub transition: bool,
+    /// Length of the crossfade in seconds.
+    pub transition_duration: f32,
+    /// How often lit segments randomly drop out or dim for a moment, from 0
+    /// for never to 1. Not supported by [`RenderMode::Mesh`].
+    pub flicker: f32,
+    /// Style of unlit segments. If unset, unlit segments are not drawn.
+    #[serde(with = "super::serde_iced::option_style")]
+    pub off_fill: Option<iced::widget::canvas::Style>,
+}
+
+/// How the segments of a digit are turned into geometry.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize,
+)]
+pub enum RenderMode {
+    /// Fill the outline of each segment as a canvas path.
+    #[default]
+    Path,
+    /// Triangulate the segments into a mesh and send it to the GPU directly.
+    /// Rounded corners and the glow are not supported and this only works
+    /// with the wgpu renderer.
+    Mesh,
+    /// Stroke the outline of each segment instead of filling it.
+    Outline,
+    /// Fill each segment faintly and stroke its outline.
+    FilledOutline,
+}
+
+impl RenderMode {
+    pub const ALL: [Self; 4] =
+        [Self::Path, Self::Mesh, Self::Outline, Self::FilledOutline];
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Path => "Filled",
+            Self::Mesh => "Mesh",
+            Self::Outline => "Outline",
+            Self::FilledOutline => "Filled outline",
+        })
+    }
+}
+
+/// Layout of the segments of a digit.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize,
+)]
+pub enum DisplayKind {
+    /// Uses `A1`, `D1` and `G1` for the whole top, bottom and middle bars.
+    SevenSegment,
+    /// Uses `A1` and `D1` for the whole top and bottom bars.
+    FourteenSegment,
+    #[default]
+    SixteenSegment,
+}
+
+impl DisplayKind {
+    pub const ALL: [Self; 3] = [
+        Self::SevenSegment,
+        Self::FourteenSegment,
+        Self::SixteenSegment,
+    ];
+
+    /// How `segment` is drawn, or `None` if this kind does not have it.
+    pub fn shape(
+        self,
+        segment: Segment,
+    ) -> Option<geometry::SegmentShape<'static>> {
+        if segment == Segment::DP {
+            return Some(geometry::SegmentShape::Dot);
+        }
+        let index = segment as usize;
+        match self {
+            Self::SevenSegment => geometry::SEVEN_SEGMENT_INSTRUCTIONS[index],
+            Self::FourteenSegment => {
+                geometry::FOURTEEN_SEGMENT_INSTRUCTIONS[index]
+            }
+            Self::SixteenSegment => Some(geometry::SEGMENT_INSTRUCTIONS[index]),
+        }
+        .map(geometry::SegmentShape::Outline)
+    }
+
+    /// All segments this kind has.
+    pub fn segments(self) -> SegmentBits {
+        SegmentBits::all()
+            .iter()
+            .filter(|&segment| self.shape(segment).is_some())
+            .fold(SegmentBits::new(), |bits, segment| bits | segment)
+    }
+
+    /// Maps the segments of a sixteen-segment glyph onto this kind. A lit
+    /// half of a split bar lights the whole bar if this kind does not split
+    /// it, and segments this kind does not have are dropped.
+    pub fn normalize(self, bits: SegmentBits) -> SegmentBits {
+        use Segment::*;
+
+        let available = self.segments();
+        let mut bits = bits;
+        for (half, whole) in [(A2, A1), (D2, D1), (G2, G1)] {
+            if bits.contains(half) && !available.contains(half) {
+                bits |= whole;
+            }
+        }
+        bits & available
+    }
+}
+
+impl fmt::Display for DisplayKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SevenSegment => "7 segments",
+            Self::FourteenSegment => "14 segments",
+            Self::SixteenSegment => "16 segments",
+        })
+    }
 }
 
 pub struct DigitDisplay {
     options: DigitOptions,
-    cache: SegmentsCache,
+    /// Geometry of whole digits by the segments they show. As the geometry
+    /// includes the fill, the caches belong to a single display, unlike its
+    /// [`SegmentPaths`].
+    glyph_caches: RefCell<HashMap<SegmentBits, Cache>>,
+    /// Outlines projected from the current options, shared with the other
+    /// displays of the same geometry
+    paths: Arc<SegmentPaths>,
+    blink_on: bool,
+    /// Seed of the current tick of the flicker effect
+    flicker_seed: u64,
+    /// Segments which never light up, like on a broken module
+    dead_segments: SegmentBits,
+    /// Segments which are always lit
+    stuck_segments: SegmentBits,
 }
 
 pub const SEGMENT_COUNT: usize = 17;
 
+/// Number of distinct glyphs a display keeps cached before starting over.
+const GLYPH_CACHE_LIMIT: usize = 256;
+
+/// Opacity of the fill in [`RenderMode::FilledOutline`].
+const OUTLINE_FILL_ALPHA: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum Segment {
     A1 = 0,
@@ -42,15 +244,94 @@ pub enum Segment {
     DP,
 }
 
+/// Canonical names of the segments in enum order, used for both printing and
+/// parsing them.
+const SEGMENT_NAMES: [&str; SEGMENT_COUNT] = [
+    "A1", "A2", "B", "C", "D1", "D2", "E", "F", "G1", "G2", "H", "I", "J", "K",
+    "L", "M", "DP",
+];
+
+impl Segment {
+    pub const fn name(&self) -> &'static str {
+        SEGMENT_NAMES[*self as usize]
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSegmentError(pub String);
+
+impl fmt::Display for ParseSegmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown segment `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseSegmentError {}
+
+/// Parses the name of a segment, ignoring the case like in `a1` or `dp`.
+impl FromStr for Segment {
+    type Err = ParseSegmentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SEGMENT_NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(s))
+            .and_then(|segment| Segment::try_from(segment as u8).ok())
+            .ok_or_else(|| ParseSegmentError(s.into()))
+    }
+}
+
+/// Serialized as its name, like `"A1"`.
+impl Serialize for Segment {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Segment {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl TryFrom<u8> for Segment {
     type Error = ();
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        if value < SEGMENT_COUNT as u8 {
-            Ok(unsafe { std::mem::transmute::<u8, Segment>(value) })
-        } else {
-            Err(())
-        }
+        use Segment::*;
+
+        Ok(match value {
+            0 => A1,
+            1 => A2,
+            2 => B,
+            3 => C,
+            4 => D1,
+            5 => D2,
+            6 => E,
+            7 => F,
+            8 => G1,
+            9 => G2,
+            10 => H,
+            11 => I,
+            12 => J,
+            13 => K,
+            14 => L,
+            15 => M,
+            16 => DP,
+            _ => return Err(()),
+        })
     }
 }
 
@@ -62,9 +343,47 @@ impl SegmentBits {
         Self(0)
     }
 
+    /// All segments set, including the decimal point.
+    pub const fn all() -> Self {
+        Self((1 << SEGMENT_COUNT) - 1)
+    }
+
     pub const fn is_empty(&self) -> bool {
         self.0 == 0
     }
+
+    pub const fn is_full(&self) -> bool {
+        self.0 == Self::all().0
+    }
+
+    /// Number of set segments.
+    pub const fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn contains(&self, segment: Segment) -> bool {
+        self.0 & (1 << segment as u8) != 0
+    }
+
+    pub fn insert(&mut self, segment: Segment) {
+        self.0 |= 1 << segment as u8;
+    }
+
+    pub fn remove(&mut self, segment: Segment) {
+        self.0 &= !(1 << segment as u8);
+    }
+
+    pub fn toggle(&mut self, segment: Segment) {
+        self.0 ^= 1 << segment as u8;
+    }
+
+    /// Iterates over the set segments in enum order.
+    pub fn iter(&self) -> impl Iterator<Item = Segment> {
+        let bits = *self;
+        (0..SEGMENT_COUNT as u8)
+            .filter_map(|segment| Segment::try_from(segment).ok())
+            .filter(move |&segment| bits & segment)
+    }
 }
 
 impl Default for SegmentBits {
@@ -73,6 +392,61 @@ impl Default for SegmentBits {
     }
 }
 
+/// Lists the set segments like `A1|B|C|DP`, or `0` if none are set.
+impl fmt::Display for SegmentBits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("0");
+        }
+        for (i, segment) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str("|")?;
+            }
+            f.write_str(segment.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialized as the list of the names of the set segments, like
+/// `["A1", "B", "DP"]`, rather than as the raw bits.
+impl Serialize for SegmentBits {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for SegmentBits {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let segments = Vec::<Segment>::deserialize(deserializer)?;
+        Ok(segments
+            .into_iter()
+            .fold(Self::new(), |bits, segment| bits | segment))
+    }
+}
+
+/// Parses segment names separated by `|` or `,` like `A1|B|C|DP`, which
+/// round-trips with the [`Display`](fmt::Display) impl. `0` and the empty
+/// string are no segments.
+impl FromStr for SegmentBits {
+    type Err = ParseSegmentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || s == "0" {
+            return Ok(Self::new());
+        }
+        s.split(['|', ','])
+            .map(|name| name.trim().parse::<Segment>())
+            .try_fold(Self::new(), |bits, segment| Ok(bits | segment?))
+    }
+}
+
 impl From<u32> for SegmentBits {
     fn from(value: u32) -> Self {
         Self(value)
@@ -85,6 +459,79 @@ impl From<SegmentBits> for u32 {
     }
 }
 
+/// Number of bytes of [`SegmentBits::to_hardware_bytes`].
+pub const HARDWARE_BYTES: usize = 3;
+
+/// Wiring of the segments to the bits of the word sent to a device, like
+/// the outputs of a chain of shift registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentOrder {
+    /// Bit of the word driving each segment, indexed by [`Segment`]
+    bits: [u8; SEGMENT_COUNT],
+}
+
+impl SegmentOrder {
+    /// Segment `n` is bit `n`, as used by the 16-segment SPI driver of the
+    /// [Cheetah firmware](https://github.com/CatoLynx/Cheetah_Firmware).
+    pub const CHEETAH: Self = Self {
+        bits: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+    };
+
+    /// Segment `n` is bit `16 - n`, for drivers wired starting with the
+    /// decimal point.
+    pub const REVERSED: Self = Self {
+        bits: [16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+    };
+
+    /// Wiring where `bits[n]` is the bit driving the segment with index `n`.
+    /// Returns `None` if a bit does not fit into [`HARDWARE_BYTES`] or drives
+    /// several segments.
+    pub fn new(bits: [u8; SEGMENT_COUNT]) -> Option<Self> {
+        let mut used = 0u32;
+        for &bit in &bits {
+            if usize::from(bit) >= HARDWARE_BYTES * 8 || used & 1 << bit != 0 {
+                return None;
+            }
+            used |= 1 << bit;
+        }
+        Some(Self { bits })
+    }
+
+    /// Bit of the word driving `segment`.
+    pub const fn bit(&self, segment: Segment) -> u8 {
+        self.bits[segment as usize]
+    }
+}
+
+impl SegmentBits {
+    /// Encodes the segments as a big-endian word with the bits wired as
+    /// given by `order`, independent of the order of the [`Segment`] enum.
+    pub fn to_hardware_bytes(
+        self,
+        order: &SegmentOrder,
+    ) -> [u8; HARDWARE_BYTES] {
+        let word = self
+            .iter()
+            .fold(0u32, |word, segment| word | 1 << order.bit(segment));
+        let [_, bytes @ ..] = word.to_be_bytes();
+        bytes
+    }
+
+    /// Decodes a word encoded by [`Self::to_hardware_bytes`]. Bits which are
+    /// not wired to a segment are ignored.
+    pub fn from_hardware_bytes(
+        bytes: [u8; HARDWARE_BYTES],
+        order: &SegmentOrder,
+    ) -> Self {
+        let [a, b, c] = bytes;
+        let word = u32::from_be_bytes([0, a, b, c]);
+        SegmentBits::all()
+            .iter()
+            .filter(|&segment| word & 1 << order.bit(segment) != 0)
+            .fold(Self::new(), |bits, segment| bits | segment)
+    }
+}
+
 impl BitOr for Segment {
     type Output = SegmentBits;
 
@@ -121,11 +568,95 @@ impl BitAnd<Segment> for SegmentBits {
     type Output = bool;
 
     fn bitand(self, rhs: Segment) -> Self::Output {
-        self.0 & (1 << rhs as u8) != 0
+        self.contains(rhs)
+    }
+}
+
+impl BitOrAssign<Segment> for SegmentBits {
+    fn bitor_assign(&mut self, rhs: Segment) {
+        *self = *self | rhs;
     }
 }
 
-pub type SegmentsCache = [Cache; SEGMENT_COUNT];
+impl BitOrAssign for SegmentBits {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl BitAndAssign for SegmentBits {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl Not for SegmentBits {
+    type Output = Self;
+
+    /// Inverts the valid segment bits, leaving the unused high bits unset.
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::all().0)
+    }
+}
+
+/// Outlines of the segments, which only depend on the geometry of the digit.
+///
+/// Displays whose options differ only in their colors share the same paths,
+/// which are computed once. The paths never change. Changing the options of
+/// a display looks up the paths for its new geometry instead, leaving the
+/// other displays untouched, and paths no longer used by any display are
+/// dropped.
+struct SegmentPaths {
+    /// Outline of each segment
+    outlines: [Path; SEGMENT_COUNT],
+    /// Expanded outlines drawn as the halo, if the glow is enabled
+    glow: Option<[Path; SEGMENT_COUNT]>,
+}
+
+/// Options which determine the [`SegmentPaths`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PathsKey {
+    kind: DisplayKind,
+    drawing: geometry::DrawingOptions,
+    glow_spread: Option<f32>,
+}
+
+/// Paths of every geometry currently in use.
+static SHARED_PATHS: Mutex<Vec<(PathsKey, Weak<SegmentPaths>)>> =
+    Mutex::new(Vec::new());
+
+impl PathsKey {
+    fn new(options: &DigitOptions) -> Self {
+        Self {
+            kind: options.kind,
+            drawing: options.drawing_options(),
+            glow_spread: options.glow.then_some(options.glow_spread),
+        }
+    }
+}
+
+impl SegmentPaths {
+    /// Paths for the geometry of `options`, reusing those of another display
+    /// with the same geometry.
+    fn shared(options: &DigitOptions) -> Arc<Self> {
+        let key = PathsKey::new(options);
+        let mut shared = SHARED_PATHS.lock().unwrap();
+        shared.retain(|(_, paths)| paths.strong_count() > 0);
+        if let Some(paths) = shared
+            .iter()
+            .find(|(shared_key, _)| *shared_key == key)
+            .and_then(|(_, paths)| paths.upgrade())
+        {
+            return paths;
+        }
+        let paths = Arc::new(Self {
+            outlines: segment_paths(options),
+            glow: glow_paths(options),
+        });
+        shared.push((key, Arc::downgrade(&paths)));
+        paths
+    }
+}
 
 impl Default for DigitOptions {
     fn default() -> Self {
@@ -140,9 +671,219 @@ impl DigitOptions {
             thickness: 5.7,
             gap: 1.3,
             slant: 0.,
-            fill: iced::widget::canvas::Style::Solid(Color::from_rgb(
+            aspect: 1.,
+            corner_radius: 0.,
+            rotation: 0.,
+            zoom: 1.,
+            mirror_x: false,
+            upside_down: false,
+            render_mode: RenderMode::Path,
+            outline_width: 1.,
+            crisp: false,
+            batched: false,
+            kind: DisplayKind::SixteenSegment,
+            on_fill: iced::widget::canvas::Style::Solid(Color::from_rgb(
                 1., 0., 0.,
             )),
+            brightness: 1.,
+            glow: false,
+            glow_intensity: 0.3,
+            glow_spread: 2.,
+            transition: false,
+            transition_duration: 0.15,
+            flicker: 0.,
+            off_fill: None,
+        }
+    }
+
+    pub const fn with_colors(on: Color, off: Color) -> Self {
+        Self {
+            on_fill: iced::widget::canvas::Style::Solid(on),
+            off_fill: Some(iced::widget::canvas::Style::Solid(off)),
+            ..Self::new()
+        }
+    }
+
+    /// Style lit segments are drawn with, dimmed by the brightness.
+    pub fn lit_fill(&self) -> Style {
+        self.dimmed(&self.on_fill)
+    }
+
+    /// Applies the brightness to `style` if it is a solid color.
+    pub fn dimmed(&self, style: &Style) -> Style {
+        match style {
+            Style::Solid(color) => Style::Solid(Color {
+                a: color.a * self.brightness,
+                ..*color
+            }),
+            gradient => gradient.clone(),
+        }
+    }
+
+    /// Changes the size of the digit, stretching the gradients of the fills
+    /// along with it.
+    pub fn resize(&mut self, size: Size) {
+        let scale = Vector::new(
+            size.width / self.size.width,
+            size.height / self.size.height,
+        );
+        let stretch = |style: &mut Style| {
+            if let Style::Gradient(Gradient::Linear(linear)) = style {
+                for point in [&mut linear.start, &mut linear.end] {
+                    *point = Point::new(point.x * scale.x, point.y * scale.y);
+                }
+            }
+        };
+        stretch(&mut self.on_fill);
+        if let Some(off_fill) = &mut self.off_fill {
+            stretch(off_fill);
+        }
+        self.size = size;
+    }
+
+    /// Two-stop gradient fading from `top` to `bottom` across the height of
+    /// the digit.
+    pub fn vertical_gradient(&self, top: Color, bottom: Color) -> Style {
+        let half_height = self.size.height * 0.5;
+        Style::Gradient(Gradient::Linear(
+            canvas::gradient::Linear::new(
+                Point::new(0., -half_height),
+                Point::new(0., half_height),
+            )
+            .add_stop(0., top)
+            .add_stop(1., bottom),
+        ))
+    }
+
+    /// Whether both options give the segments the same outlines.
+    pub fn same_geometry(&self, other: &Self) -> bool {
+        PathsKey::new(self) == PathsKey::new(other)
+    }
+
+    /// Whether both options draw the digits the same way. Options only
+    /// arranging or animating the digits are ignored.
+    pub fn same_look(&self, other: &Self) -> bool {
+        self.same_geometry(other)
+            && self.render_mode == other.render_mode
+            && self.outline_width == other.outline_width
+            && self.batched == other.batched
+            && self.on_fill == other.on_fill
+            && self.brightness == other.brightness
+            && self.glow_intensity == other.glow_intensity
+            && self.off_fill == other.off_fill
+    }
+
+    /// Opacity with which segments are filled, or `None` if they are only
+    /// outlined.
+    pub fn fill_alpha(&self) -> Option<f32> {
+        match self.render_mode {
+            RenderMode::Path | RenderMode::Mesh => Some(1.),
+            RenderMode::Outline => None,
+            RenderMode::FilledOutline => Some(OUTLINE_FILL_ALPHA),
+        }
+    }
+
+    /// Width of the stroke around each segment, or `None` if segments are
+    /// not outlined.
+    pub fn stroke_width(&self) -> Option<f32> {
+        match self.render_mode {
+            RenderMode::Path | RenderMode::Mesh => None,
+            RenderMode::Outline | RenderMode::FilledOutline => {
+                Some(self.outline_width)
+            }
+        }
+    }
+
+    /// Style of the halo around segments lit with the style `lit`.
+    pub fn glow_fill(&self, lit: &Style) -> Style {
+        faded(lit, self.glow_intensity)
+    }
+
+    /// Finds the segment under `position`, which is relative to the center
+    /// of the digit.
+    pub fn segment_at(&self, position: Point) -> Option<Segment> {
+        let options = &self.drawing_options();
+        SegmentBits::all().iter().find(|&segment| {
+            match self.kind.shape(segment) {
+                Some(geometry::SegmentShape::Outline(instructions)) => {
+                    geometry::polygon_contains(
+                        &instructions.outline(options),
+                        position,
+                    )
+                }
+                Some(geometry::SegmentShape::Dot) => {
+                    let center =
+                        geometry::project_point(&geometry::DP, options);
+                    center.distance(position)
+                        <= geometry::DP_RADIUS * self.thickness
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Size of the digit on screen, magnified by the zoom.
+    pub fn zoomed_size(&self) -> Size {
+        Size::new(self.size.width * self.zoom, self.size.height * self.zoom)
+    }
+
+    /// Factor by which the digit is scaled to fit into `bounds`.
+    pub fn fit_scale(&self, bounds: Size) -> f32 {
+        (bounds.width / self.size.width).min(bounds.height / self.size.height)
+    }
+
+    /// Flip applied to the whole digit after rotating it.
+    fn mirror(&self) -> Mat2 {
+        let mirror = if self.mirror_x {
+            geometry::MIRROR_X
+        } else {
+            Mat2::IDENTITY
+        };
+        if self.upside_down {
+            mirror * geometry::MIRROR_XY
+        } else {
+            mirror
+        }
+    }
+
+    /// Column at which the digit at `col` of a row with `cols` digits is
+    /// placed on the board.
+    pub fn board_column(&self, col: usize, cols: usize) -> usize {
+        if self.mirror_x != self.upside_down {
+            cols - 1 - col
+        } else {
+            col
+        }
+    }
+
+    /// Row at which the digits at `row` of a board with `rows` rows are
+    /// placed.
+    pub fn board_row(&self, row: usize, rows: usize) -> usize {
+        if self.upside_down {
+            rows - 1 - row
+        } else {
+            row
+        }
+    }
+
+    pub fn drawing_options(&self) -> geometry::DrawingOptions {
+        // The gaps are limited by the bars of the scaled glyph
+        let scaled_size =
+            Size::new(self.size.width * self.aspect, self.size.height);
+        let gaps = geometry::gap_range(scaled_size, self.thickness);
+        geometry::DrawingOptions {
+            size: self.size,
+            gap: self.gap.clamp(*gaps.start(), *gaps.end()),
+            thickness: self.thickness,
+            pos_transform: Mat2::from_diagonal(glam::Vec2::new(
+                self.aspect,
+                1.,
+            )),
+            transform: self.mirror()
+                * Mat2::from_angle(self.rotation)
+                * geometry::shear(self.slant),
+            corner_radius: self.corner_radius,
+            snap: self.crisp.then(|| 1. / self.zoom),
         }
     }
 }
@@ -150,8 +891,13 @@ impl DigitOptions {
 impl DigitDisplay {
     pub fn new(options: DigitOptions) -> Self {
         Self {
+            paths: SegmentPaths::shared(&options),
             options,
-            cache: SegmentsCache::default(),
+            glyph_caches: RefCell::default(),
+            blink_on: true,
+            flicker_seed: 0,
+            dead_segments: SegmentBits::new(),
+            stuck_segments: SegmentBits::new(),
         }
     }
 
@@ -160,32 +906,153 @@ impl DigitDisplay {
     }
 
     pub fn set_options(&mut self, options: DigitOptions) {
-        self.clear_cache();
-        self.options = options;
+        let old = std::mem::replace(&mut self.options, options);
+        self.options_changed(&old);
     }
 
     pub fn modify_options(&mut self, modifier: impl FnOnce(&mut DigitOptions)) {
-        self.clear_cache();
+        let old = self.options.clone();
         modifier(&mut self.options);
+        self.options_changed(&old);
+    }
+
+    /// Rebuilds only what depends on the options which differ from `old`.
+    /// The outlines are kept unless the geometry changed, and the cached
+    /// glyphs are kept for options which do not affect their look, like the
+    /// crossfade.
+    fn options_changed(&mut self, old: &DigitOptions) {
+        if !self.options.same_geometry(old) {
+            self.paths = SegmentPaths::shared(&self.options);
+            self.clear_cache();
+        } else if !self.options.same_look(old) {
+            self.clear_cache();
+        }
+    }
+
+    pub fn blink_on(&self) -> bool {
+        self.blink_on
+    }
+
+    /// Shows or hides the lit segments. The cached geometry is kept, as only
+    /// the visibility changes.
+    pub fn set_blink_on(&mut self, blink_on: bool) {
+        self.blink_on = blink_on;
+    }
+
+    /// Rolls which segments flicker until the next tick.
+    pub fn advance_flicker(&mut self) {
+        self.flicker_seed = self.flicker_seed.wrapping_add(1);
+    }
+
+    pub fn dead_segments(&self) -> SegmentBits {
+        self.dead_segments
+    }
+
+    pub fn set_dead_segments(&mut self, dead_segments: SegmentBits) {
+        self.dead_segments = dead_segments;
+    }
+
+    pub fn stuck_segments(&self) -> SegmentBits {
+        self.stuck_segments
+    }
+
+    pub fn set_stuck_segments(&mut self, stuck_segments: SegmentBits) {
+        self.stuck_segments = stuck_segments;
+    }
+
+    /// Segments which are actually lit when showing `segments`, taking the
+    /// blinking and the dead and stuck segments into account.
+    pub fn shown(&self, segments: SegmentBits) -> SegmentBits {
+        let segments = if self.blink_on {
+            segments & !self.dead_segments
+        } else {
+            SegmentBits::new()
+        };
+        segments | self.stuck_segments
     }
 
     fn clear_cache(&self) {
-        self.cache.iter().for_each(Cache::clear);
+        self.glyph_caches.borrow_mut().clear();
     }
 
     pub fn instantiate(
         &self,
         segments: SegmentBits,
     ) -> iced::Element<'_, crate::app::Message, iced::Theme, iced::Renderer>
+    {
+        self.instantiate_with_fill(segments, None)
+    }
+
+    /// Like [`Self::instantiate`], but lit segments are drawn with `on_fill`
+    /// instead of the shared style if it is set. Such digits are not cached.
+    pub fn instantiate_with_fill(
+        &self,
+        segments: SegmentBits,
+        on_fill: Option<Style>,
+    ) -> iced::Element<'_, crate::app::Message, iced::Theme, iced::Renderer>
+    {
+        use iced::widget;
+
+        if self.options.render_mode == RenderMode::Mesh {
+            return mesh::DigitMesh {
+                digit: self,
+                segments,
+                on_fill,
+            }
+            .into();
+        }
+
+        widget::canvas(DigitProgram {
+            digit: self,
+            segments,
+            on_edit: None,
+            on_fill,
+        })
+        .width(Length::Fixed(self.options.zoomed_size().width))
+        .height(Length::Fixed(self.options.zoomed_size().height))
+        .into()
+    }
+
+    /// Narrow cell showing a colon as two dots in the style of the digits,
+    /// like between the hours and minutes of a clock.
+    pub fn instantiate_colon(
+        &self,
+        lit: bool,
+    ) -> iced::Element<'_, crate::app::Message, iced::Theme, iced::Renderer>
+    {
+        use iced::widget;
+
+        let options = &self.options;
+        widget::canvas(colon::ColonProgram { digit: self, lit })
+            .width(Length::Fixed(
+                options.size.width * colon::COLON_WIDTH * options.zoom,
+            ))
+            .height(Length::Fixed(options.size.height * options.zoom))
+            .into()
+    }
+
+    /// Like [`Self::instantiate`], but clicking a segment toggles it and
+    /// emits the edited segments.
+    pub fn instantiate_editor(
+        &self,
+        segments: SegmentBits,
+        on_edit: fn(SegmentBits) -> crate::app::Message,
+    ) -> iced::Element<'_, crate::app::Message, iced::Theme, iced::Renderer>
     {
         use iced::widget;
 
+        // Leaves room for the decimal point beside the digit on both sides,
+        // so that the digit stays centered
+        let options = &self.options;
+        let margin = 2. * (options.gap.max(0.) + options.thickness);
         widget::canvas(DigitProgram {
             digit: self,
             segments,
+            on_edit: Some(on_edit),
+            on_fill: None,
         })
-        .width(Length::Fixed(self.options.size.width))
-        .height(Length::Fixed(self.options.size.height))
+        .width(Length::Fixed((options.size.width + margin) * options.zoom))
+        .height(Length::Fixed(self.options.size.height * self.options.zoom))
         .into()
     }
 }
@@ -193,81 +1060,771 @@ impl DigitDisplay {
 struct DigitProgram<'a> {
     digit: &'a DigitDisplay,
     segments: SegmentBits,
+    on_edit: Option<fn(SegmentBits) -> crate::app::Message>,
+    /// Overrides the style of lit segments
+    on_fill: Option<Style>,
+}
+
+/// Crossfade of a digit from the segments shown before the last change.
+#[derive(Debug, Clone, Copy, Default)]
+struct Transition {
+    from: SegmentBits,
+    to: SegmentBits,
+    start: Option<Instant>,
 }
 
 impl DigitProgram<'_> {
-    fn draw_segments(
+    /// Style of the lit segments, which may be overridden.
+    fn lit_fill(&self) -> Style {
+        match &self.on_fill {
+            Some(on_fill) => self.digit.options.dimmed(on_fill),
+            None => self.digit.options.lit_fill(),
+        }
+    }
+
+    fn segment_at(
+        &self,
+        bounds: iced::Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> Option<Segment> {
+        let position = cursor.position_in(bounds)?;
+        let center = Vector::new(bounds.width, bounds.height) * 0.5;
+        let scale = self.digit.options.fit_scale(bounds.size());
+        let offset = position - center;
+        self.digit
+            .options
+            .segment_at(Point::new(offset.x / scale, offset.y / scale))
+    }
+
+    /// Draws the digit from the cache of its glyph, so that each distinct
+    /// glyph on the board is only built once.
+    fn draw_cached(
+        &self,
+        renderer: &iced::Renderer,
+        size: Size,
+        segments: SegmentBits,
+    ) -> Vec<Geometry> {
+        let mut caches = self.digit.glyph_caches.borrow_mut();
+        // Raw segments could otherwise grow the caches without bound
+        if caches.len() >= GLYPH_CACHE_LIMIT && !caches.contains_key(&segments)
+        {
+            caches.clear();
+        }
+        let cache = caches.entry(segments).or_default();
+        vec![cache.draw(renderer, size, |frame| {
+            frame.translate(Vector::new(size.width, size.height) * 0.5);
+            frame.scale(self.digit.options.fit_scale(size));
+            self.paint_layers(
+                frame,
+                !segments,
+                &[(segments, 1.)],
+                &self.digit.options.lit_fill(),
+            );
+        })]
+    }
+
+    /// Draws the lit and the unlit segments into a new geometry, bypassing
+    /// the caches, for digits whose lit segments have a style of their own.
+    fn draw_uncached(
         &self,
         renderer: &iced::Renderer,
-    ) -> [Geometry; SEGMENT_COUNT] {
-        let size = self.digit.options.size;
-        let options = &geometry::DrawingOptions {
+        size: Size,
+        segments: SegmentBits,
+        on_fill: &Style,
+    ) -> Vec<Geometry> {
+        self.draw_layers(renderer, size, !segments, &[(segments, 1.)], on_fill)
+    }
+
+    /// Draws the digit partway through a crossfade, with segments turning on
+    /// at the given `progress` from 0 to 1.
+    fn draw_transition(
+        &self,
+        renderer: &iced::Renderer,
+        size: Size,
+        transition: Transition,
+        progress: f32,
+        on_fill: &Style,
+    ) -> Vec<Geometry> {
+        let Transition { from, to, .. } = transition;
+        // Fading segments are drawn over their unlit style
+        self.draw_layers(
+            renderer,
             size,
-            gap: self.digit.options.gap,
-            thickness: self.digit.options.thickness,
-            ..Default::default()
-        };
+            !(from & to),
+            &[
+                (from & to, 1.),
+                (to & !from, progress),
+                (from & !to, 1. - progress),
+            ],
+            on_fill,
+        )
+    }
 
-        let segments_cache = &self.digit.cache;
-        let fill = &self.digit.options.fill;
-
-        std::array::from_fn(|segment| {
-            let cache = &segments_cache[segment];
-            cache.draw(renderer, size, |frame| {
-                frame.translate(Vector::new(size.width, size.height) * 0.5);
-                frame.scale(1.);
-                match geometry::SEGMENT_INSTRUCTIONS.get(segment) {
-                    Some(instructions) => {
-                let path = Path::new(|d| {
-                            geometry::draw_path(
+    /// Draws the digit with some lit segments dropped or dimmed.
+    fn draw_flicker(
+        &self,
+        renderer: &iced::Renderer,
+        size: Size,
+        segments: SegmentBits,
+        flicker: Flicker,
+        on_fill: &Style,
+    ) -> Vec<Geometry> {
+        let steady = segments & !(flicker.dropped | flicker.dimmed);
+        self.draw_layers(
+            renderer,
+            size,
+            !steady,
+            &[(steady, 1.), (flicker.dimmed, flicker::FLICKER_DIM)],
+            on_fill,
+        )
+    }
+
+    /// Draws the `unlit` segments and the lit layers of segments with their
+    /// opacity, bypassing the caches.
+    fn draw_layers(
+        &self,
+        renderer: &iced::Renderer,
+        size: Size,
+        unlit: SegmentBits,
+        layers: &[(SegmentBits, f32)],
+        on_fill: &Style,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, size);
+        frame.translate(Vector::new(size.width, size.height) * 0.5);
+        frame.scale(self.digit.options.fit_scale(size));
+        self.paint_layers(&mut frame, unlit, layers, on_fill);
+        vec![frame.into_geometry()]
+    }
+
+    /// Paints the `unlit` segments, then the halos and the lit segments of
+    /// each layer with its opacity.
+    fn paint_layers(
+        &self,
+        frame: &mut Frame,
+        unlit: SegmentBits,
+        layers: &[(SegmentBits, f32)],
+        on_fill: &Style,
+    ) {
+        for (path, style) in self.fills(unlit, layers, on_fill) {
+            paint(frame, &path, &style, &self.digit.options);
+        }
+    }
+
+    /// Paths painted by [`Self::paint_layers`] and their styles, in the
+    /// order they are painted. Segments missing from the kind of display
+    /// are left out. Batched digits combine the segments of each style into
+    /// a new path.
+    fn fills(
+        &self,
+        unlit: SegmentBits,
+        layers: &[(SegmentBits, f32)],
+        on_fill: &Style,
+    ) -> Vec<(Cow<'_, Path>, Style)> {
+        let options = &self.digit.options;
+        let paths = &self.digit.paths;
+        // Segments, whether they are drawn as the halo, and their style
+        let mut groups = Vec::new();
+        if let Some(off_fill) = &options.off_fill {
+            groups.push((unlit, false, off_fill.clone()));
+        }
+        if paths.glow.is_some() {
+            let glow_fill = options.glow_fill(on_fill);
+            for &(segments, alpha) in layers {
+                groups.push((segments, true, faded(&glow_fill, alpha)));
+            }
+        }
+        for &(segments, alpha) in layers {
+            groups.push((segments, false, faded(on_fill, alpha)));
+        }
+
+        let shown = options.kind.segments();
+        let groups = groups
+            .into_iter()
+            .map(|(segments, glow, style)| (segments & shown, glow, style))
+            .filter(|(segments, ..)| !segments.is_empty());
+        if options.batched && self.on_edit.is_none() {
+            let outline = options.drawing_options();
+            let glow = glow_options(options).drawing_options();
+            return groups
+                .map(|(segments, is_glow, style)| {
+                    let drawing = if is_glow { &glow } else { &outline };
+                    let path = segment_path(options.kind, segments, drawing);
+                    (Cow::Owned(path), style)
+                })
+                .collect();
+        }
+        groups
+            .flat_map(|(segments, glow, style)| {
+                let paths = match (glow, &paths.glow) {
+                    (true, Some(glow)) => glow,
+                    _ => &paths.outlines,
+                };
+                segments.iter().map(move |segment| {
+                    (Cow::Borrowed(&paths[segment as usize]), style.clone())
+                })
+            })
+            .collect()
+    }
+}
+
+/// Multiplies the opacity of every color of `style` by `alpha`.
+pub fn faded(style: &Style, alpha: f32) -> Style {
+    let fade = |color: Color| Color {
+        a: color.a * alpha,
+        ..color
+    };
+    match style.clone() {
+        Style::Solid(color) => Style::Solid(fade(color)),
+        Style::Gradient(Gradient::Linear(mut linear)) => {
+            for stop in linear.stops.iter_mut().flatten() {
+                stop.color = fade(stop.color);
+            }
+            Style::Gradient(Gradient::Linear(linear))
+        }
+    }
+}
+
+/// Fills and strokes a segment with `style` as set by the render mode.
+fn paint(
+    frame: &mut Frame,
+    path: &Path,
+    style: &Style,
+    options: &DigitOptions,
+) {
+    if let Some(alpha) = options.fill_alpha() {
+        frame.fill(
+            path,
+            Fill {
+                style: faded(style, alpha),
+                rule: Rule::NonZero,
+            },
+        );
+    }
+    if let Some(width) = options.stroke_width() {
+        frame.stroke(
+            path,
+            Stroke {
+                style: style.clone(),
+                width,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn segment_paths(options: &DigitOptions) -> [Path; SEGMENT_COUNT] {
+    let drawing = &options.drawing_options();
+    std::array::from_fn(|segment| {
+        let segment = Segment::try_from(segment as u8).unwrap();
+        segment_path(options.kind, SegmentBits::new() | segment, drawing)
+    })
+}
+
+/// Outlines of all `segments` combined into one path.
+fn segment_path(
+    kind: DisplayKind,
+    segments: SegmentBits,
+    options: &geometry::DrawingOptions,
+) -> Path {
+    Path::new(|d| {
+        for segment in segments.iter() {
+            match kind.shape(segment) {
+                Some(geometry::SegmentShape::Outline(instructions)) => {
+                    geometry::draw_path(
                         d,
-                                instructions.points,
+                        instructions.points,
                         &options.transform(instructions.transform),
                     )
-                });
-                frame.fill(
-                    &path,
-                    Fill {
-                        style: fill.clone(),
-                        rule: Rule::NonZero,
-                    },
-                );
-                    }
-                    None => {
-                        // TODO: dot
-                    }
                 }
-            })
-        })
+                Some(geometry::SegmentShape::Dot) => geometry::draw_dot(
+                    d,
+                    &geometry::DP,
+                    geometry::DP_RADIUS,
+                    options,
+                ),
+                None => (),
+            }
+        }
+    })
+}
+
+/// Outlines of the segments widened by the glow spread on every side.
+fn glow_paths(options: &DigitOptions) -> Option<[Path; SEGMENT_COUNT]> {
+    options.glow.then(|| segment_paths(&glow_options(options)))
+}
+
+/// Options of the halo, whose segments are thicker by the glow spread on
+/// every side.
+fn glow_options(options: &DigitOptions) -> DigitOptions {
+    DigitOptions {
+        thickness: options.thickness + 2. * options.glow_spread,
+        ..options.clone()
     }
 }
 
 impl Program<crate::app::Message> for DigitProgram<'_> {
-    type State = ();
+    type State = Cell<Transition>;
 
-    fn draw(
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: iced::Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> (canvas::event::Status, Option<crate::app::Message>) {
+        use iced::mouse;
+
+        let Some(on_edit) = self.on_edit else {
+            return (canvas::event::Status::Ignored, None);
+        };
+
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(
+                mouse::Button::Left,
+            )) => match self.segment_at(bounds, cursor) {
+                Some(segment) => {
+                    let mut segments = self.segments;
+                    segments.toggle(segment);
+                    (canvas::event::Status::Captured, Some(on_edit(segments)))
+                }
+                None => (canvas::event::Status::Ignored, None),
+            },
+            _ => (canvas::event::Status::Ignored, None),
+        }
+    }
+
+    fn mouse_interaction(
         &self,
         _state: &Self::State,
+        bounds: iced::Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> iced::mouse::Interaction {
+        if self.on_edit.is_some() && self.segment_at(bounds, cursor).is_some() {
+            iced::mouse::Interaction::Pointer
+        } else {
+            iced::mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
         renderer: &iced::Renderer,
         _theme: &iced::Theme,
         bounds: iced::Rectangle,
         _cursor: iced::mouse::Cursor,
     ) -> Vec<Geometry> {
-        if self.segments.is_empty() || bounds.size() != self.digit.options.size
-        {
+        mesh::check_renderer(renderer);
+        let options = &self.digit.options;
+        let segments = self.digit.shown(self.segments);
+        // Blinking switches the segments without a crossfade
+        if self.digit.blink_on && self.on_edit.is_none() {
+            let now = Instant::now();
+            let mut transition = state.get();
+            if transition.to != segments {
+                transition = Transition {
+                    from: transition.to,
+                    to: segments,
+                    start: options.transition.then_some(now),
+                };
+                state.set(transition);
+            }
+            let progress = transition.start.map_or(1., |start| {
+                (now - start).as_secs_f32() / options.transition_duration
+            });
+            if options.transition && progress < 1. {
+                return self.draw_transition(
+                    renderer,
+                    bounds.size(),
+                    transition,
+                    progress,
+                    &self.lit_fill(),
+                );
+            }
+
+            if options.flicker > 0. {
+                let seed = flicker::digit_seed(
+                    self.digit.flicker_seed,
+                    bounds.position(),
+                );
+                let flicker = Flicker::roll(seed, segments, options.flicker);
+                if !flicker.is_empty() {
+                    return self.draw_flicker(
+                        renderer,
+                        bounds.size(),
+                        segments,
+                        flicker,
+                        &self.lit_fill(),
+                    );
+                }
+            }
+        }
+
+        if segments.is_empty() && options.off_fill.is_none() {
             return Vec::new();
         }
 
-        let segments = self.draw_segments(renderer);
-        let mut shown = Vec::with_capacity(segments.len());
+        if self.on_fill.is_some() {
+            // The cached segments are drawn with the shared style
+            self.draw_uncached(
+                renderer,
+                bounds.size(),
+                segments,
+                &self.lit_fill(),
+            )
+        } else {
+            self.draw_cached(renderer, bounds.size(), segments)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of paths painted for a digit showing `segments`.
+    fn fills(options: DigitOptions, segments: SegmentBits) -> usize {
+        let display = DigitDisplay::new(options);
+        let program = DigitProgram {
+            digit: &display,
+            segments,
+            on_edit: None,
+            on_fill: None,
+        };
+        let on_fill = display.options.lit_fill();
+        program.fills(!segments, &[(segments, 1.)], &on_fill).len()
+    }
+
+    #[test]
+    fn decimal_point_alone_is_one_fill() {
+        let dp = SegmentBits::new() | Segment::DP;
+        assert_eq!(fills(DigitOptions::default(), dp), 1);
+    }
+
+    #[test]
+    fn segment_from_u8() {
+        for value in 0..SEGMENT_COUNT as u8 {
+            let segment = Segment::try_from(value).unwrap();
+            assert_eq!(segment as u8, value);
+        }
+        assert_eq!(Segment::try_from(SEGMENT_COUNT as u8), Err(()));
+        assert_eq!(Segment::try_from(u8::MAX), Err(()));
+    }
+
+    #[test]
+    fn segment_names_round_trip() {
+        for segment in SegmentBits::all().iter() {
+            let name = segment.name();
+            assert_eq!(name.parse(), Ok(segment));
+            assert_eq!(name.to_lowercase().parse(), Ok(segment));
+        }
+        assert_eq!("Dp".parse(), Ok(Segment::DP));
+        assert_eq!(
+            "A3".parse::<Segment>(),
+            Err(ParseSegmentError("A3".into()))
+        );
+        assert!("".parse::<Segment>().is_err());
+    }
+
+    #[test]
+    fn segment_bits_from_str() {
+        let bits = Segment::A1 | Segment::B | Segment::DP;
+        assert_eq!("A1|B|DP".parse(), Ok(bits));
+        assert_eq!(" a1, b ,Dp ".parse(), Ok(bits));
+        assert_eq!(bits.to_string().parse(), Ok(bits));
+        assert_eq!("0".parse(), Ok(SegmentBits::new()));
+        assert_eq!("".parse(), Ok(SegmentBits::new()));
+        assert_eq!(
+            "A1|X".parse::<SegmentBits>(),
+            Err(ParseSegmentError("X".into()))
+        );
+    }
+
+    #[test]
+    fn hardware_bytes_round_trip() {
+        let bits = Segment::A1 | Segment::B;
+        assert_eq!(bits.to_hardware_bytes(&SegmentOrder::CHEETAH), [0, 0, 5]);
+        assert_eq!(
+            bits.to_hardware_bytes(&SegmentOrder::REVERSED),
+            [0x01, 0x40, 0]
+        );
+
+        let font = &crate::segments::segmented_font::DEFAULT;
+        for order in [SegmentOrder::CHEETAH, SegmentOrder::REVERSED] {
+            for (_, bits) in font.iter() {
+                let bytes = bits.to_hardware_bytes(&order);
+                assert_eq!(
+                    SegmentBits::from_hardware_bytes(bytes, &order),
+                    bits
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn options_round_trip_through_toml() {
+        let on_fill = canvas::gradient::Linear::new(
+            Point::new(0., -40.),
+            Point::new(0., 40.),
+        )
+        .add_stop(0., Color::from_rgb(1., 0.5, 0.))
+        .add_stop(1., Color::from_rgba(0.2, 0., 1., 0.5));
+        let options = DigitOptions {
+            size: Size::new(30., 50.),
+            gap: -1.5,
+            slant: 0.2,
+            mirror_x: true,
+            render_mode: RenderMode::FilledOutline,
+            kind: DisplayKind::SevenSegment,
+            on_fill: Style::Gradient(Gradient::Linear(on_fill)),
+            off_fill: Some(Style::Solid(Color::from_rgb(0.1, 0., 0.))),
+            ..DigitOptions::default()
+        };
+        let toml = toml::to_string(&options).unwrap();
+        assert_eq!(toml::from_str::<DigitOptions>(&toml).unwrap(), options);
+    }
+
+    #[test]
+    fn segment_bits_serialize_as_names() {
+        let bits = Segment::A1 | Segment::B | Segment::DP;
+        let json = serde_json::to_string(&bits).unwrap();
+        assert_eq!(json, r#"["A1","B","DP"]"#);
+        assert_eq!(serde_json::from_str::<SegmentBits>(&json).unwrap(), bits);
+        assert_eq!(
+            serde_json::from_str::<SegmentBits>(r#"["dp","a1","b"]"#).unwrap(),
+            bits
+        );
+        assert!(serde_json::from_str::<SegmentBits>(r#"["A1","Z"]"#).is_err());
+    }
+
+    #[test]
+    fn unlit_segments_are_ghosts() {
+        let options = DigitOptions {
+            off_fill: Some(Style::Solid(Color::from_rgb(0.1, 0., 0.))),
+            ..DigitOptions::default()
+        };
+        assert_eq!(fills(options.clone(), SegmentBits::new()), 17);
+        let seven_segment = DigitOptions {
+            kind: DisplayKind::SevenSegment,
+            ..options
+        };
+        assert_eq!(fills(seven_segment, SegmentBits::new()), 8);
+    }
 
-        for (segment, geometry) in segments.into_iter().enumerate() {
-            let segment = Segment::try_from(segment as u8).unwrap();
-            if self.segments & segment {
-                shown.push(geometry);
+    #[test]
+    fn segments_combine_into_bits() {
+        let bits = Segment::A1 | Segment::DP;
+        assert!(bits.contains(Segment::A1));
+        assert!(bits.contains(Segment::DP));
+        assert!(!bits.contains(Segment::A2));
+        assert_eq!(bits.count(), 2);
+        assert_eq!(u32::from(bits), 1 | 1 << 16);
+        assert_eq!(bits.iter().collect::<Vec<_>>(), [Segment::A1, Segment::DP]);
+    }
+
+    #[test]
+    fn inverts_only_valid_segments() {
+        let top = !(Segment::A1 | Segment::A2);
+        assert_eq!(top.count(), SEGMENT_COUNT as u32 - 2);
+        assert!(!top.contains(Segment::A1) && !top.contains(Segment::A2));
+        assert!(top.contains(Segment::DP));
+        assert!((!SegmentBits::all()).is_empty());
+        assert_eq!(!SegmentBits::new(), SegmentBits::all());
+    }
+
+    #[test]
+    fn toggling_twice_restores_segments() {
+        let original = Segment::A1 | Segment::G2;
+        for segment in [Segment::A1, Segment::B, Segment::DP] {
+            let mut bits = original;
+            bits.toggle(segment);
+            assert_ne!(bits, original);
+            bits.toggle(segment);
+            assert_eq!(bits, original);
+        }
+    }
+
+    #[test]
+    fn slant_shears_outlines() {
+        let outline = |slant: f32| {
+            let options = DigitOptions {
+                slant,
+                ..DigitOptions::default()
             }
+            .drawing_options();
+            geometry::project(&geometry::A1, &options)
+        };
+        let (upright, slanted) = (outline(0.), outline(0.2));
+        assert_ne!(upright, slanted);
+        for (upright, slanted) in upright.iter().zip(&slanted) {
+            // The top half leans to the right, keeping the heights
+            assert_eq!(upright.y, slanted.y);
+            assert!(slanted.x > upright.x);
         }
+    }
+
+    #[test]
+    fn draws_into_larger_bounds() {
+        let renderer = iced::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+            iced_tiny_skia::Backend::new(),
+            iced::Font::DEFAULT,
+            iced::Pixels(16.),
+        ));
+        let display = DigitDisplay::new(DigitOptions::default());
+        let program = DigitProgram {
+            digit: &display,
+            segments: Segment::A1 | Segment::B,
+            on_edit: None,
+            on_fill: None,
+        };
+        let size = display.options.size;
+        let bounds = iced::Rectangle::with_size(Size::new(
+            size.width * 2.,
+            size.height * 2.,
+        ));
+        assert_eq!(display.options.fit_scale(bounds.size()), 2.);
+        let geometry = program.draw(
+            &Cell::default(),
+            &renderer,
+            &iced::Theme::Dark,
+            bounds,
+            iced::mouse::Cursor::Unavailable,
+        );
+        assert_eq!(geometry.len(), 1);
+    }
 
-        shown
+    #[test]
+    fn zoom_magnifies_digits() {
+        // Width of the outlines of all segments on screen
+        let width = |zoom: f32| {
+            let options = DigitOptions {
+                zoom,
+                ..DigitOptions::default()
+            };
+            let scale = options.fit_scale(options.zoomed_size());
+            let drawing_options = options.drawing_options();
+            let xs =
+                geometry::SEGMENT_INSTRUCTIONS.iter().flat_map(|segment| {
+                    segment.outline(&drawing_options).into_iter().map(|p| p.x)
+                });
+            let (min, max) = xs.fold((f32::MAX, f32::MIN), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+            (max - min) * scale
+        };
+        assert!((width(2.) / width(1.) - 2.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fourteen_segments_have_no_split_bars() {
+        let kind = DisplayKind::FourteenSegment;
+        assert!(kind.shape(Segment::A2).is_none());
+        assert!(kind.shape(Segment::D2).is_none());
+        assert_eq!(kind.segments().count(), 15);
+        let options = DigitOptions {
+            kind,
+            ..DigitOptions::default()
+        };
+        assert_eq!(fills(options, SegmentBits::all()), 15);
+    }
+
+    #[test]
+    fn gaps_keep_outlines_from_folding() {
+        // Twice the signed area of a polygon
+        let area = |points: &[Point]| -> f32 {
+            let next = points.iter().cycle().skip(1);
+            points
+                .iter()
+                .zip(next)
+                .map(|(a, b)| a.x * b.y - b.x * a.y)
+                .sum()
+        };
+        let outlines = |gap: f32| {
+            let options = DigitOptions {
+                gap,
+                ..DigitOptions::default()
+            };
+            let drawing_options = options.drawing_options();
+            let outlines: Vec<_> = geometry::SEGMENT_INSTRUCTIONS
+                .iter()
+                .map(|segment| segment.outline(&drawing_options))
+                .collect();
+            (drawing_options.gap, outlines)
+        };
+
+        let (_, reference) = outlines(0.);
+        let max = *geometry::gap_range(
+            DigitOptions::default().size,
+            DigitOptions::default().thickness,
+        )
+        .end();
+        assert!(max < 8.);
+        for (gap, clamped) in [(-2., -2.), (0., 0.), (8., max)] {
+            let (actual, outlines) = outlines(gap);
+            assert_eq!(actual, clamped);
+            for (outline, reference) in outlines.iter().zip(&reference) {
+                let (area, reference) = (area(outline), area(reference));
+                assert!(area != 0. && area.signum() == reference.signum());
+            }
+        }
+    }
+
+    #[test]
+    fn turning_twice_is_identity() {
+        let options = DigitOptions {
+            slant: 0.2,
+            ..DigitOptions::default()
+        };
+        let upside_down = DigitOptions {
+            upside_down: true,
+            ..options.clone()
+        };
+        let (upright, turned) =
+            (options.drawing_options(), upside_down.drawing_options());
+        let twice = turned.transform(geometry::MIRROR_XY);
+        for segment in geometry::SEGMENT_INSTRUCTIONS {
+            let outline = segment.outline(&upright);
+            let turned_outline = segment.outline(&turned);
+            for (p, q) in outline.iter().zip(&turned_outline) {
+                assert!(p.distance(Point::new(-q.x, -q.y)) < 1e-4);
+            }
+            for (p, q) in outline.iter().zip(&segment.outline(&twice)) {
+                assert!(p.distance(*q) < 1e-4);
+            }
+        }
+        let dp = geometry::project_point(&geometry::DP, &upright);
+        let turned_dp = geometry::project_point(&geometry::DP, &turned);
+        assert!(dp.distance(Point::new(-turned_dp.x, -turned_dp.y)) < 1e-4);
+    }
+
+    #[test]
+    fn outlines_each_lit_segment() {
+        let eight = crate::segments::segmented_font::DEFAULT.glyph(&'8');
+        for render_mode in [RenderMode::Outline, RenderMode::FilledOutline] {
+            let options = DigitOptions {
+                render_mode,
+                ..DigitOptions::default()
+            };
+            assert!(options.stroke_width().is_some());
+            assert_eq!(fills(options, eight), eight.count() as usize);
+        }
+    }
+
+    #[test]
+    fn batched_digits_fill_once_per_style() {
+        let options = DigitOptions {
+            glow: true,
+            off_fill: Some(Style::Solid(Color::BLACK)),
+            ..Default::default()
+        };
+        let eight = "A1,A2,B,C,D1,D2,E,F,G1,G2".parse().unwrap();
+        // The 10 lit segments, their halos and the 7 unlit segments
+        assert_eq!(fills(options.clone(), eight), 2 * 10 + 7);
+        let batched = DigitOptions {
+            batched: true,
+            ..options
+        };
+        assert_eq!(fills(batched.clone(), eight), 3);
+        assert_eq!(fills(batched, SegmentBits::new()), 1);
     }
 }
@@ -0,0 +1,57 @@
+//! Narrow cell between the digits of a clock, which shows the colon as two
+//! dots instead of taking up a whole digit.
+
+use glam::Vec2;
+use iced::{
+    widget::canvas::{Frame, Geometry, Path, Program},
+    Point, Vector,
+};
+
+use super::{geometry, paint, DigitDisplay};
+
+/// Width of a colon cell relative to the width of a digit.
+pub const COLON_WIDTH: f32 = 0.4;
+
+pub struct ColonProgram<'a> {
+    pub digit: &'a DigitDisplay,
+    pub lit: bool,
+}
+
+impl Program<crate::app::Message> for ColonProgram<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        _theme: &iced::Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let options = &self.digit.options;
+        // The dots blink together with the segments of the digits
+        let style = if self.lit && self.digit.blink_on {
+            options.lit_fill()
+        } else if let Some(off_fill) = &options.off_fill {
+            off_fill.clone()
+        } else {
+            return Vec::new();
+        };
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.translate(Vector::new(bounds.width, bounds.height) * 0.5);
+        frame.scale(bounds.height / options.size.height);
+
+        // The dots sit halfway between the middle and the top and bottom
+        // bars, slanted like the digits
+        let transform = options.drawing_options().transform;
+        let radius = geometry::DP_RADIUS * options.thickness;
+        for y in [-0.25, 0.25] {
+            let center = transform * Vec2::new(0., y * options.size.height);
+            let dot = Path::circle(Point::new(center.x, center.y), radius);
+            paint(&mut frame, &dot, &style, options);
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
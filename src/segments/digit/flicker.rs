@@ -0,0 +1,65 @@
+//! Lit segments randomly dropping out or dimming for a moment, like on
+//! failing or analog hardware.
+
+use iced::Point;
+
+use super::SegmentBits;
+
+/// Chance of a lit segment to flicker during one tick at full intensity.
+const FLICKER_CHANCE: f32 = 0.1;
+/// Opacity of dimmed segments relative to the lit ones.
+pub const FLICKER_DIM: f32 = 0.4;
+
+/// Lit segments which flicker during one tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flicker {
+    /// Segments which are not drawn at all
+    pub dropped: SegmentBits,
+    /// Segments drawn with [`FLICKER_DIM`] opacity
+    pub dimmed: SegmentBits,
+}
+
+impl Flicker {
+    /// Rolls which of the lit `segments` flicker with a chance scaled by
+    /// `intensity` from 0 to 1. The same arguments always give the same
+    /// result.
+    pub fn roll(seed: u64, segments: SegmentBits, intensity: f32) -> Self {
+        let chance = intensity.clamp(0., 1.) * FLICKER_CHANCE;
+        let mut state = seed;
+        let mut flicker = Self::default();
+        for segment in segments.iter() {
+            let random = splitmix64(&mut state);
+            // The top 24 bits give a uniform float in 0..1
+            if ((random >> 40) as f32 / (1 << 24) as f32) < chance {
+                if random & 1 == 0 {
+                    flicker.dropped |= segment;
+                } else {
+                    flicker.dimmed |= segment;
+                }
+            }
+        }
+        flicker
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (self.dropped | self.dimmed).is_empty()
+    }
+}
+
+/// Seed of the digit at `position` on screen during the tick `seed`, so
+/// that the digits flicker independently of each other.
+pub fn digit_seed(seed: u64, position: Point) -> u64 {
+    let mut state = (u64::from(position.x.to_bits()) << 32)
+        | u64::from(position.y.to_bits());
+    seed ^ splitmix64(&mut state)
+}
+
+/// Advances `state` and returns the next number of the SplitMix64
+/// generator.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -1,6 +1,9 @@
 //! See <https://en.wikipedia.org/wiki/Sixteen-segment_display#/media/File:16-segmente.png>
 
-use std::f32::consts::{FRAC_1_SQRT_2, SQRT_2};
+use std::{
+    f32::consts::{FRAC_1_SQRT_2, SQRT_2},
+    ops::RangeInclusive,
+};
 
 use glam::{Mat2, Vec2};
 use iced::{widget::canvas::path, Point, Size};
@@ -31,15 +34,33 @@ impl SegmentPoint {
     pub const fn with_gap_offset(self, gap_offset: Vec2) -> Self {
         Self { gap_offset, ..self }
     }
+
+    /// Mirrors the point along the vertical axis.
+    pub const fn mirror_x(self) -> Self {
+        const fn mirror(v: Vec2) -> Vec2 {
+            Vec2::new(-v.x, v.y)
+        }
+        Self {
+            pos: mirror(self.pos),
+            thickness_offset: mirror(self.thickness_offset),
+            gap_offset: mirror(self.gap_offset),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DrawingOptions {
     pub size: Size,
     pub gap: f32,
     pub thickness: f32,
+    /// Transform of the positions of the points relative to the size,
+    /// applied before offsetting them by the thickness and the gap so that
+    /// those stay the same.
     pub pos_transform: Mat2,
     pub transform: Mat2,
+    pub corner_radius: f32,
+    /// Spacing of the grid which projected points are rounded to.
+    pub snap: Option<f32>,
 }
 
 impl Default for DrawingOptions {
@@ -50,6 +71,8 @@ impl Default for DrawingOptions {
             size: Size::new(100., 200.),
             pos_transform: Mat2::IDENTITY,
             transform: Mat2::IDENTITY,
+            corner_radius: 0.,
+            snap: None,
         }
     }
 }
@@ -63,44 +86,176 @@ impl DrawingOptions {
     }
 }
 
+/// Shear that leans the top of a digit to the right by `slant` units of x
+/// per unit of y. Pivots around the vertical center.
+pub fn shear(slant: f32) -> Mat2 {
+    Mat2::from_cols(Vec2::X, Vec2::new(-slant, 1.))
+}
+
+/// Gaps for which no segment outline folds over itself. Beyond the
+/// thickness, the pointed ends of the segments turn inside out, and the
+/// shortest bars vanish once the gap eats up their length. Negative gaps
+/// extend the segments into each other.
+pub fn gap_range(size: Size, thickness: f32) -> RangeInclusive<f32> {
+    let (half_width, half_height) = (size.width * 0.5, size.height * 0.5);
+    // Shortest bars, ignoring the pointed ends
+    let horizontal = (half_width - thickness) / (0.5 + DGAP_INNER);
+    let vertical = (half_height - 1.5 * thickness) / (2. * DGAP_INNER);
+    let inner_vertical = (half_height - 1.5 * thickness) * 0.5;
+    let diagonal = ((half_width - 1.5 * thickness) * 0.5)
+        .min(half_height * 0.5 - thickness);
+    let max = [horizontal, vertical, inner_vertical, diagonal]
+        .into_iter()
+        .fold(thickness, f32::min);
+    -thickness..=max.max(0.)
+}
+
 pub fn draw_path(
     d: &mut path::Builder,
     points: &[SegmentPoint],
+    options: &DrawingOptions,
+) {
+    let corners =
+        round_corners(&project(points, options), options.corner_radius);
+    let Some((first, rest)) = corners.split_first() else {
+        return;
+    };
+
+    d.move_to(first.start);
+    if !first.is_sharp() {
+        d.quadratic_curve_to(first.control, first.end);
+    }
+
+    for corner in rest {
+        d.line_to(corner.start);
+        if !corner.is_sharp() {
+            d.quadratic_curve_to(corner.control, corner.end);
+        }
+    }
+
+    d.close();
+}
+
+/// A corner of a segment outline, rounded by a quadratic curve from `start`
+/// to `end`. The points of a sharp corner are all equal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corner {
+    pub start: Point,
+    pub control: Point,
+    pub end: Point,
+}
+
+impl Corner {
+    pub fn is_sharp(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Rounds the convex corners of a polygon by `radius`. The rounding is
+/// clamped to half of the adjacent edges so that neighboring corners don't
+/// overlap.
+pub fn round_corners(points: &[Point], radius: f32) -> Vec<Corner> {
+    let vec = |p: Point| Vec2::new(p.x, p.y);
+    let n = points.len();
+
+    // Positive for clockwise polygons in screen space
+    let orientation: f32 = (0..n)
+        .map(|i| vec(points[i]).perp_dot(vec(points[(i + 1) % n])))
+        .sum();
+
+    (0..n)
+        .map(|i| {
+            let prev = vec(points[(i + n - 1) % n]);
+            let cur = vec(points[i]);
+            let next = vec(points[(i + 1) % n]);
+
+            let to_prev = prev - cur;
+            let to_next = next - cur;
+            let convex = (cur - prev).perp_dot(to_next) * orientation > 0.;
+            let cut = radius
+                .min(to_prev.length() * 0.5)
+                .min(to_next.length() * 0.5);
+
+            if !convex || cut <= 0. {
+                return Corner {
+                    start: points[i],
+                    control: points[i],
+                    end: points[i],
+                };
+            }
+
+            Corner {
+                start: point(cur + to_prev.normalize() * cut),
+                control: points[i],
+                end: point(cur + to_next.normalize() * cut),
+            }
+        })
+        .collect()
+}
+
+/// Draws the decimal point as a circle around `center`, with a radius
+/// relative to the segment thickness.
+pub fn draw_dot(
+    d: &mut path::Builder,
+    center: &SegmentPoint,
+    radius: f32,
+    options: &DrawingOptions,
+) {
+    d.circle(project_point(center, options), radius * options.thickness);
+}
+
+/// Projects the points of a segment into drawing space.
+pub fn project(
+    points: &[SegmentPoint],
+    options: &DrawingOptions,
+) -> Vec<Point> {
+    points.iter().map(|sp| project_point(sp, options)).collect()
+}
+
+pub fn project_point(
+    sp: &SegmentPoint,
     &DrawingOptions {
         gap,
         thickness: thick,
         size,
         pos_transform,
         transform,
+        snap,
+        ..
     }: &DrawingOptions,
-) {
-    let Some((first, rest)) = points.split_first() else {
-        return;
-    };
-
+) -> Point {
     let pos_ref = Vec2::new(size.width, size.height) * 0.5;
 
-    d.move_to(point(
-        transform
-            * (pos_transform
-                * (pos_ref * first.pos + thick * first.thickness_offset)
-                + gap * first.gap_offset),
-    ));
-
-    for sp in rest {
-        d.line_to(point(
-            transform
-                * (pos_transform
-                    * (pos_ref * sp.pos + thick * sp.thickness_offset)
-                    + gap * sp.gap_offset),
-        ));
-    }
-
-    d.close();
+    let projected = transform
+        * (pos_transform * (pos_ref * sp.pos)
+            + thick * sp.thickness_offset
+            + gap * sp.gap_offset);
+    point(match snap {
+        Some(grid) => (projected / grid).round() * grid,
+        None => projected,
+    })
+}
 
-    const fn point(vec: Vec2) -> Point {
-        Point::new(vec.x, vec.y)
+/// Tests whether `p` lies within the closed polygon spanned by `points`.
+pub fn polygon_contains(points: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    let mut prev = match points.last() {
+        Some(&last) => last,
+        None => return false,
+    };
+    for &cur in points {
+        if (cur.y > p.y) != (prev.y > p.y)
+            && p.x < (prev.x - cur.x) * (p.y - cur.y) / (prev.y - cur.y) + cur.x
+        {
+            inside = !inside;
+        }
+        prev = cur;
     }
+    inside
+}
+
+const fn point(vec: Vec2) -> Point {
+    Point::new(vec.x, vec.y)
 }
 
 /// Indices to render a 4-point segment using triangle strip encoding.
@@ -110,6 +265,16 @@ pub const TRI_STRIP_5: [usize; 5] = [0, 1, 4, 2, 3];
 /// Indices to render a 6-point segment using triangle strip encoding.
 pub const TRI_STRIP_6: [usize; 6] = [0, 1, 5, 2, 4, 3];
 
+/// Triangle strip indices for a segment outline with `len` points.
+pub fn tri_strip(len: usize) -> Option<&'static [usize]> {
+    match len {
+        4 => Some(&TRI_STRIP_4),
+        5 => Some(&TRI_STRIP_5),
+        6 => Some(&TRI_STRIP_6),
+        _ => None,
+    }
+}
+
 const DGAP: f32 = FRAC_1_SQRT_2 * 0.5;
 const DGAP_INNER: f32 = SQRT_2 * 0.5;
 const TOP_LEFT: Vec2 = Vec2::NEG_ONE;
@@ -202,17 +367,110 @@ pub const I: [SegmentPoint; 4] = [
         .with_gap_offset(Vec2::NEG_Y),
 ];
 
+/// Top bar spanning the whole width, made of [`A1`] and its mirror image.
+pub const A: [SegmentPoint; 6] = [
+    A1[0],
+    A1[1],
+    A1[1].mirror_x(),
+    A1[0].mirror_x(),
+    A1[4].mirror_x(),
+    A1[4],
+];
+
+/// Middle bar spanning the whole width, made of [`G1`] and its mirror image.
+pub const G: [SegmentPoint; 6] = [
+    G1[0],
+    G1[1],
+    G1[1].mirror_x(),
+    G1[0].mirror_x(),
+    G1[4].mirror_x(),
+    G1[4],
+];
+
+/// Center of the decimal point, beside the bottom right corner and apart
+/// from the bars by the gap.
+pub const DP: SegmentPoint = SegmentPoint::new(Vec2::ONE)
+    .with_thickness_offset(Vec2::new(DP_RADIUS, -DP_RADIUS))
+    .with_gap_offset(Vec2::X);
+
+/// Radius of the decimal point relative to the segment thickness.
+pub const DP_RADIUS: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy)]
 pub struct SegmentInstruction<'a> {
     pub points: &'a [SegmentPoint],
     pub transform: Mat2,
 }
 
-pub const SEGMENT_INSTRUCTIONS: [SegmentInstruction; 16] = {
-    const IDENT: Mat2 = Mat2::IDENTITY;
-    const MIRROR_X: Mat2 = Mat2::from_diagonal(Vec2::new(-1., 1.));
-    const MIRROR_Y: Mat2 = Mat2::from_diagonal(Vec2::new(1., -1.));
-    const MIRROR_XY: Mat2 = Mat2::from_diagonal(Vec2::new(-1., -1.));
+impl SegmentInstruction<'_> {
+    /// Outline of the segment in drawing space, as traced by [`draw_path`]
+    /// before its corners are rounded.
+    pub fn outline(&self, options: &DrawingOptions) -> Vec<Point> {
+        project(self.points, &options.transform(self.transform))
+    }
+}
+
+/// How a segment is drawn.
+#[derive(Debug, Clone, Copy)]
+pub enum SegmentShape<'a> {
+    Outline(SegmentInstruction<'a>),
+    /// A circle around [`DP`] with a radius of [`DP_RADIUS`]
+    Dot,
+}
+
+const IDENT: Mat2 = Mat2::IDENTITY;
+pub const MIRROR_X: Mat2 = Mat2::from_diagonal(Vec2::new(-1., 1.));
+const MIRROR_Y: Mat2 = Mat2::from_diagonal(Vec2::new(1., -1.));
+pub const MIRROR_XY: Mat2 = Mat2::from_diagonal(Vec2::new(-1., -1.));
+
+/// Outlines of a seven-segment digit, indexed like [`SEGMENT_INSTRUCTIONS`].
+/// The whole top, bottom and middle bars take the place of `A1`, `D1` and
+/// `G1`. Segments missing from the display are `None`.
+pub const SEVEN_SEGMENT_INSTRUCTIONS: [Option<SegmentInstruction>; 16] = {
+    let mut table = [None; 16];
+    table[0] = Some(SegmentInstruction {
+        points: &A,
+        transform: IDENT,
+    });
+    table[2] = Some(SEGMENT_INSTRUCTIONS[2]);
+    table[3] = Some(SEGMENT_INSTRUCTIONS[3]);
+    table[4] = Some(SegmentInstruction {
+        points: &A,
+        transform: MIRROR_Y,
+    });
+    table[6] = Some(SEGMENT_INSTRUCTIONS[6]);
+    table[7] = Some(SEGMENT_INSTRUCTIONS[7]);
+    table[8] = Some(SegmentInstruction {
+        points: &G,
+        transform: IDENT,
+    });
+    table
+};
 
+/// Outlines of a fourteen-segment digit, indexed like
+/// [`SEGMENT_INSTRUCTIONS`]. The whole top and bottom bars take the place of
+/// `A1` and `D1`, and `A2` and `D2` are `None`.
+pub const FOURTEEN_SEGMENT_INSTRUCTIONS: [Option<SegmentInstruction>; 16] = {
+    let mut table = [None; 16];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = Some(SEGMENT_INSTRUCTIONS[i]);
+        i += 1;
+    }
+    table[0] = Some(SegmentInstruction {
+        points: &A,
+        transform: IDENT,
+    });
+    table[1] = None;
+    table[4] = Some(SegmentInstruction {
+        points: &A,
+        transform: MIRROR_Y,
+    });
+    table[5] = None;
+    table
+};
+
+pub const SEGMENT_INSTRUCTIONS: [SegmentInstruction; 16] = {
     [
         /*A1*/
         SegmentInstruction {
@@ -296,3 +554,123 @@ pub const SEGMENT_INSTRUCTIONS: [SegmentInstruction; 16] = {
         },
     ]
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_points(actual: &[Point], expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (a, &(x, y)) in actual.iter().zip(expected) {
+            assert!(
+                (a.x - x).abs() < 1e-3 && (a.y - y).abs() < 1e-3,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn projects_a1() {
+        let options = DrawingOptions::default();
+        assert_points(
+            &project(&A1, &options),
+            &[
+                (-43.2929, -94.7071),
+                (-38., -100.),
+                (-1., -100.),
+                (-1., -88.),
+                (-36.5858, -88.),
+            ],
+        );
+    }
+
+    #[test]
+    fn projects_a1_with_thickness_and_without_gap() {
+        let options = DrawingOptions {
+            gap: 0.,
+            thickness: 10.,
+            ..Default::default()
+        };
+        assert_points(
+            &project(&A1, &options),
+            &[
+                (-45., -95.),
+                (-40., -100.),
+                (0., -100.),
+                (0., -90.),
+                (-40., -90.),
+            ],
+        );
+    }
+
+    #[test]
+    fn projects_slanted_a1() {
+        let options = DrawingOptions {
+            gap: 4.,
+            transform: shear(0.25),
+            ..Default::default()
+        };
+        assert_points(
+            &project(&A1, &options),
+            &[
+                (-18.7322, -95.4142),
+                (-13., -100.),
+                (23., -100.),
+                (20., -88.),
+                (-13.1716, -88.),
+            ],
+        );
+    }
+
+    #[test]
+    fn decimal_point_clears_bars() {
+        let options = DrawingOptions::default();
+        let center = project_point(&DP, &options);
+        let radius = DP_RADIUS * options.thickness;
+        // C and D2 meet in the bottom right corner
+        for instruction in [SEGMENT_INSTRUCTIONS[3], SEGMENT_INSTRUCTIONS[5]] {
+            for p in instruction.outline(&options) {
+                assert!(center.x - radius >= p.x + options.gap - 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn rounds_convex_corners() {
+        let outline = project(&A1, &DrawingOptions::default());
+        let sharp = round_corners(&outline, 0.);
+        assert!(sharp.iter().all(Corner::is_sharp));
+
+        let rounded = round_corners(&outline, 2.);
+        // Rounded corners start and end at distinct points
+        let points: usize = rounded
+            .iter()
+            .map(|corner| if corner.is_sharp() { 1 } else { 2 })
+            .sum();
+        assert!(points > outline.len());
+        for (corner, &p) in rounded.iter().zip(&outline) {
+            // The curves are controlled by the original corners
+            assert_eq!(corner.control, p);
+            assert!(corner.start.distance(p) <= 2. + 1e-4);
+            assert!(corner.end.distance(p) <= 2. + 1e-4);
+        }
+    }
+
+    #[test]
+    fn snaps_a1_to_grid() {
+        let options = DrawingOptions {
+            snap: Some(1.),
+            ..Default::default()
+        };
+        assert_points(
+            &project(&A1, &options),
+            &[
+                (-43., -95.),
+                (-38., -100.),
+                (-1., -100.),
+                (-1., -88.),
+                (-37., -88.),
+            ],
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Renders digits as triangle meshes instead of filled paths. Only supported
+//! by the wgpu renderer, other renderers fall back to drawing paths.
+
+use std::{
+    f32::consts::TAU,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use iced::{
+    advanced::{
+        graphics::{
+            color,
+            geometry::Renderer as _,
+            mesh::{GradientVertex2D, Indexed, Mesh, SolidVertex2D},
+        },
+        layout, mouse, renderer,
+        widget::Tree,
+        Layout, Renderer as _, Widget,
+    },
+    widget::canvas::{Gradient, Style},
+    Length, Point, Rectangle, Size, Vector,
+};
+
+use super::{geometry, DigitDisplay, DigitProgram, SegmentBits};
+
+/// Number of vertices on the outline of the decimal point.
+const DOT_VERTICES: u32 = 16;
+
+/// Set once a renderer without mesh support has drawn a digit.
+static UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the renderer used so far draws meshes. Only known after the
+/// first digit was drawn.
+pub fn meshes_supported() -> bool {
+    !UNSUPPORTED.load(Ordering::Relaxed)
+}
+
+/// Remembers whether `renderer` draws meshes, returning `false` for the
+/// software renderer which silently skips them.
+pub(super) fn check_renderer(renderer: &iced::Renderer) -> bool {
+    let supported = !matches!(renderer, iced::Renderer::TinySkia(_));
+    if !supported {
+        UNSUPPORTED.store(true, Ordering::Relaxed);
+    }
+    supported
+}
+
+pub struct DigitMesh<'a> {
+    pub digit: &'a DigitDisplay,
+    pub segments: SegmentBits,
+    pub on_fill: Option<Style>,
+}
+
+impl DigitMesh<'_> {
+    /// Triangulates the given segments, with points relative to the center
+    /// of the unscaled digit.
+    fn triangles(&self, segments: SegmentBits) -> Indexed<Point> {
+        let options = &self.digit.options.drawing_options();
+        let mut mesh = Indexed {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+
+        for segment in segments.iter() {
+            let base = mesh.vertices.len() as u32;
+            match self.digit.options.kind.shape(segment) {
+                Some(geometry::SegmentShape::Outline(instructions)) => {
+                    let Some(strip) =
+                        geometry::tri_strip(instructions.points.len())
+                    else {
+                        continue;
+                    };
+                    mesh.vertices.extend(instructions.outline(options));
+                    for triangle in strip.windows(3) {
+                        mesh.indices
+                            .extend(triangle.iter().map(|&i| base + i as u32));
+                    }
+                }
+                Some(geometry::SegmentShape::Dot) => {
+                    // Triangle fan around the center of the dot
+                    let center =
+                        geometry::project_point(&geometry::DP, options);
+                    let radius =
+                        geometry::DP_RADIUS * self.digit.options.thickness;
+                    mesh.vertices.push(center);
+                    mesh.vertices.extend((0..DOT_VERTICES).map(|i| {
+                        let angle = i as f32 / DOT_VERTICES as f32 * TAU;
+                        center + Vector::new(angle.cos(), angle.sin()) * radius
+                    }));
+                    for i in 0..DOT_VERTICES {
+                        mesh.indices.extend([
+                            base,
+                            base + 1 + i,
+                            base + 1 + (i + 1) % DOT_VERTICES,
+                        ]);
+                    }
+                }
+                None => (),
+            }
+        }
+
+        mesh
+    }
+}
+
+impl<Message> Widget<Message, iced::Theme, iced::Renderer> for DigitMesh<'_> {
+    fn size(&self) -> Size<Length> {
+        let size = self.digit.options.zoomed_size();
+        Size::new(Length::Fixed(size.width), Length::Fixed(size.height))
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        _renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let size = Widget::<Message, _, _>::size(self);
+        layout::atomic(limits, size.width, size.height)
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut iced::Renderer,
+        _theme: &iced::Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let options = &self.digit.options;
+        let segments = self.digit.shown(self.segments);
+
+        if !check_renderer(renderer) {
+            let program = DigitProgram {
+                digit: self.digit,
+                segments: self.segments,
+                on_edit: None,
+                on_fill: self.on_fill.clone(),
+            };
+            let geometry = program.draw_uncached(
+                renderer,
+                bounds.size(),
+                segments,
+                &program.lit_fill(),
+            );
+            renderer.with_translation(
+                Vector::new(bounds.x, bounds.y),
+                |renderer| renderer.draw(geometry),
+            );
+            return;
+        }
+
+        let scale = options.fit_scale(bounds.size());
+        let center = Vector::new(bounds.width, bounds.height) * 0.5;
+        let to_bounds =
+            |p: Point| Point::new(p.x * scale, p.y * scale) + center;
+
+        let on_fill = match &self.on_fill {
+            Some(on_fill) => options.dimmed(on_fill),
+            None => options.lit_fill(),
+        };
+        let mut meshes = Vec::with_capacity(2);
+        // Unlit segments are drawn behind the lit ones
+        if let Some(off_fill) = &options.off_fill {
+            meshes.push((!segments, off_fill));
+        }
+        meshes.push((segments, &on_fill));
+
+        for (segments, style) in meshes {
+            if segments.is_empty() {
+                continue;
+            }
+            let Indexed { vertices, indices } = self.triangles(segments);
+            let positions = vertices.into_iter().map(|p| {
+                let p = to_bounds(p);
+                [p.x, p.y]
+            });
+
+            let mesh = match style {
+                Style::Solid(color) => {
+                    let color = color::pack(*color);
+                    Mesh::Solid {
+                        buffers: Indexed {
+                            vertices: positions
+                                .map(|position| SolidVertex2D {
+                                    position,
+                                    color,
+                                })
+                                .collect(),
+                            indices,
+                        },
+                        size: bounds.size(),
+                    }
+                }
+                Style::Gradient(Gradient::Linear(linear)) => {
+                    let mut linear = *linear;
+                    linear.start = to_bounds(linear.start);
+                    linear.end = to_bounds(linear.end);
+                    let gradient = linear.pack();
+                    Mesh::Gradient {
+                        buffers: Indexed {
+                            vertices: positions
+                                .map(|position| GradientVertex2D {
+                                    position,
+                                    gradient,
+                                })
+                                .collect(),
+                            indices,
+                        },
+                        size: bounds.size(),
+                    }
+                }
+            };
+
+            renderer.with_translation(
+                Vector::new(bounds.x, bounds.y),
+                |renderer| renderer.draw_mesh(mesh),
+            );
+        }
+    }
+}
+
+impl<'a, Message: 'a> From<DigitMesh<'a>>
+    for iced::Element<'a, Message, iced::Theme, iced::Renderer>
+{
+    fn from(mesh: DigitMesh<'a>) -> Self {
+        Self::new(mesh)
+    }
+}
@@ -1,5 +1,9 @@
+pub mod ascii;
 mod digit;
+pub mod raster;
 pub mod segmented_font;
+pub mod serde_iced;
+pub mod svg;
 
 pub use digit::*;
 pub use segmented_font::segmented_font;
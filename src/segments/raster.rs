@@ -0,0 +1,351 @@
+//! Software rasterization of a board of digits, which works without a GPU or
+//! a window, and encoding of the rendered frames as animated GIFs.
+
+use std::io;
+
+use iced::{widget::canvas::Style, Color};
+use tiny_skia::{
+    FillRule, GradientStop, LinearGradient, Paint, PathBuilder, Pixmap, Shader,
+    SpreadMode, Transform,
+};
+
+use super::{
+    faded,
+    geometry::{self, SegmentShape},
+    svg::{BoardCell, SvgLayout},
+    DigitOptions, SegmentBits,
+};
+
+/// Speed of the color quantization of GIF frames, from 1 for the best
+/// quality to 30 for the fastest encoding.
+const GIF_QUANTIZATION_SPEED: i32 = 10;
+
+/// Draws every digit of the `board`, which is given as a list of rows, with
+/// the same geometry as [`super::svg::write_svg`]. Returns `None` if the
+/// board would have no pixels.
+pub fn rasterize(
+    board: &[Vec<BoardCell>],
+    options: &DigitOptions,
+    layout: &SvgLayout,
+) -> Option<Pixmap> {
+    let size = options.size;
+    let cols = board.iter().map(Vec::len).max().unwrap_or(0);
+    let rows = board.len();
+    let width = 2. * layout.padding
+        + cols as f32 * size.width
+        + cols.saturating_sub(1) as f32 * layout.char_spacing;
+    let height = 2. * layout.padding
+        + rows as f32 * size.height
+        + rows.saturating_sub(1) as f32 * layout.line_spacing;
+
+    let mut pixmap = Pixmap::new(width.ceil() as u32, height.ceil() as u32)?;
+    pixmap.fill(color(layout.background));
+    let painter = BoardPainter::new(options, layout, rows, cols);
+
+    #[cfg(not(feature = "parallel"))]
+    for (row, digits) in board.iter().enumerate() {
+        painter.draw_row(&mut pixmap.as_mut(), row, digits, 0.);
+    }
+
+    // Every row is drawn into its own band of the pixmap, split halfway
+    // between the rows, so that the threads never touch the same pixels
+    // and the result does not depend on the thread scheduling
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        let (width, height) = (pixmap.width(), pixmap.height());
+        let stride = width as usize * 4;
+        let mut bands = Vec::with_capacity(rows);
+        let (mut rest, mut top) = (pixmap.data_mut(), 0);
+        for position in 1..=rows {
+            let bottom = if position == rows {
+                height
+            } else {
+                let gap = layout.padding
+                    + position as f32 * (size.height + layout.line_spacing)
+                    - 0.5 * layout.line_spacing;
+                (gap.round() as u32).clamp(top, height)
+            };
+            let (band, tail) = std::mem::take(&mut rest)
+                .split_at_mut((bottom - top) as usize * stride);
+            bands.push((top, bottom - top, band));
+            (rest, top) = (tail, bottom);
+        }
+        bands.into_par_iter().enumerate().for_each(
+            |(position, (top, band_height, band))| {
+                let row = options.board_row(position, rows);
+                let Some(mut band) =
+                    tiny_skia::PixmapMut::from_bytes(band, width, band_height)
+                else {
+                    return;
+                };
+                painter.draw_row(&mut band, row, &board[row], top as f32);
+            },
+        );
+    }
+
+    Some(pixmap)
+}
+
+/// Paints and paths shared by all digits of a board.
+struct BoardPainter<'a> {
+    options: &'a DigitOptions,
+    layout: &'a SvgLayout,
+    rows: usize,
+    cols: usize,
+    on_paints: (Option<Paint<'static>>, Paint<'static>),
+    off_paints: Option<(Option<Paint<'static>>, Paint<'static>)>,
+    stroke: Option<tiny_skia::Stroke>,
+    paths: Vec<Option<tiny_skia::Path>>,
+}
+
+impl<'a> BoardPainter<'a> {
+    fn new(
+        options: &'a DigitOptions,
+        layout: &'a SvgLayout,
+        rows: usize,
+        cols: usize,
+    ) -> Self {
+        Self {
+            options,
+            layout,
+            rows,
+            cols,
+            on_paints: paints(&options.lit_fill(), options),
+            off_paints: options
+                .off_fill
+                .as_ref()
+                .map(|style| paints(style, options)),
+            stroke: options.stroke_width().map(|width| tiny_skia::Stroke {
+                width,
+                ..Default::default()
+            }),
+            paths: segment_paths(options),
+        }
+    }
+
+    /// Vertical center of the digits of a row on the board.
+    fn row_center(&self, row: usize) -> f32 {
+        let size = self.options.size;
+        let row = self.options.board_row(row, self.rows);
+        self.layout.padding
+            + row as f32 * (size.height + self.layout.line_spacing)
+            + size.height * 0.5
+    }
+
+    /// Draws the digits of a row, moved up by `top` pixels.
+    fn draw_row(
+        &self,
+        pixmap: &mut tiny_skia::PixmapMut,
+        row: usize,
+        digits: &[BoardCell],
+        top: f32,
+    ) {
+        let size = self.options.size;
+        let y = self.row_center(row) - top;
+        for (col, cell) in digits.iter().enumerate() {
+            let cell_paints = cell.color.map(|color| {
+                paints(&self.options.dimmed(&Style::Solid(color)), self.options)
+            });
+            let on_paints = cell_paints.as_ref().unwrap_or(&self.on_paints);
+            let col = self.options.board_column(col, self.cols);
+            let x = self.layout.padding
+                + col as f32 * (size.width + self.layout.char_spacing)
+                + size.width * 0.5;
+            let transform = Transform::from_translate(x, y);
+
+            for (segment, path) in self.paths.iter().enumerate() {
+                let lit =
+                    cell.segments.contains((segment as u8).try_into().unwrap());
+                let (fill_paint, stroke_paint) = match (lit, &self.off_paints) {
+                    (true, _) => on_paints,
+                    (false, Some(off_paints)) => off_paints,
+                    (false, None) => continue,
+                };
+                let Some(path) = path else {
+                    continue;
+                };
+                if let Some(paint) = fill_paint {
+                    pixmap.fill_path(
+                        path,
+                        paint,
+                        FillRule::Winding,
+                        transform,
+                        None,
+                    );
+                }
+                if let Some(stroke) = &self.stroke {
+                    pixmap.stroke_path(
+                        path,
+                        stroke_paint,
+                        stroke,
+                        transform,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Encodes `frames`, which must all have the same size, as a looping GIF
+/// animation showing `fps` frames per second.
+pub fn write_gif(
+    out: impl io::Write,
+    frames: &[Pixmap],
+    fps: f32,
+) -> Result<(), gif::EncodingError> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+    let too_large =
+        |_| io::Error::new(io::ErrorKind::InvalidInput, "board too large");
+    let width = u16::try_from(first.width()).map_err(too_large)?;
+    let height = u16::try_from(first.height()).map_err(too_large)?;
+
+    let mut encoder = gif::Encoder::new(out, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    // Delays are given in hundredths of a second
+    let delay = (100. / fps).round() as u16;
+    for pixmap in frames {
+        let mut rgba: Vec<u8> = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let pixel = pixel.demultiply();
+                [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            })
+            .collect();
+        let mut frame = gif::Frame::from_rgba_speed(
+            width,
+            height,
+            &mut rgba,
+            GIF_QUANTIZATION_SPEED,
+        );
+        frame.delay = delay;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+/// Outline of each segment relative to the center of the digit.
+fn segment_paths(options: &DigitOptions) -> Vec<Option<tiny_skia::Path>> {
+    let drawing_options = options.drawing_options();
+    SegmentBits::all()
+        .iter()
+        .map(|segment| match options.kind.shape(segment)? {
+            SegmentShape::Outline(instructions) => {
+                let corners = geometry::round_corners(
+                    &instructions.outline(&drawing_options),
+                    options.corner_radius,
+                );
+                let mut builder = PathBuilder::new();
+                for (i, corner) in corners.iter().enumerate() {
+                    let start = corner.start;
+                    if i == 0 {
+                        builder.move_to(start.x, start.y);
+                    } else {
+                        builder.line_to(start.x, start.y);
+                    }
+                    if !corner.is_sharp() {
+                        let (control, end) = (corner.control, corner.end);
+                        builder.quad_to(control.x, control.y, end.x, end.y);
+                    }
+                }
+                builder.close();
+                builder.finish()
+            }
+            SegmentShape::Dot => {
+                let center =
+                    geometry::project_point(&geometry::DP, &drawing_options);
+                PathBuilder::from_circle(
+                    center.x,
+                    center.y,
+                    geometry::DP_RADIUS * options.thickness,
+                )
+            }
+        })
+        .collect()
+}
+
+/// Paints filling and stroking segments drawn with `style`. Segments are
+/// not filled if the render mode only outlines them.
+fn paints(
+    style: &Style,
+    options: &DigitOptions,
+) -> (Option<Paint<'static>>, Paint<'static>) {
+    let fill = options
+        .fill_alpha()
+        .map(|alpha| paint(&faded(style, alpha)));
+    (fill, paint(style))
+}
+
+fn paint(style: &Style) -> Paint<'static> {
+    let shader = match style {
+        Style::Solid(c) => Shader::SolidColor(color(*c)),
+        Style::Gradient(iced::widget::canvas::Gradient::Linear(linear)) => {
+            LinearGradient::new(
+                tiny_skia::Point::from_xy(linear.start.x, linear.start.y),
+                tiny_skia::Point::from_xy(linear.end.x, linear.end.y),
+                linear
+                    .stops
+                    .iter()
+                    .flatten()
+                    .map(|stop| {
+                        GradientStop::new(stop.offset, color(stop.color))
+                    })
+                    .collect(),
+                SpreadMode::Pad,
+                Transform::identity(),
+            )
+            .unwrap_or(Shader::SolidColor(tiny_skia::Color::TRANSPARENT))
+        }
+    };
+    Paint {
+        shader,
+        anti_alias: true,
+        ..Default::default()
+    }
+}
+
+fn color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(color.r, color.g, color.b, color.a)
+        .unwrap_or(tiny_skia::Color::BLACK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segments::segmented_font::DEFAULT;
+
+    /// Pixels of a rasterized digit which are partly covered by segments.
+    fn blurred_pixels(ch: char, crisp: bool) -> usize {
+        let options = DigitOptions {
+            crisp,
+            ..DigitOptions::default()
+        };
+        let layout = SvgLayout {
+            char_spacing: 0.,
+            line_spacing: 0.,
+            padding: 8.,
+            background: Color::BLACK,
+        };
+        let board = [vec![DEFAULT.glyph(&ch).into()]];
+        let pixmap = rasterize(&board, &options, &layout).unwrap();
+        pixmap
+            .pixels()
+            .iter()
+            .filter(|pixel| !matches!(pixel.red(), 0 | 255))
+            .count()
+    }
+
+    #[test]
+    fn crisp_edges_snap_to_pixels() {
+        // Only the pointed ends of the segments stay blurred
+        let (smooth, crisp) =
+            (blurred_pixels('H', false), blurred_pixels('H', true));
+        assert!(crisp > 0);
+        assert!(crisp * 3 < smooth, "{crisp} of {smooth} pixels blurred");
+    }
+}
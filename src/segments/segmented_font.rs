@@ -1,21 +1,389 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, LazyLock},
+};
 
-use super::SegmentBits;
+use super::{DisplayKind, ParseSegmentError, Segment, SegmentBits};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SegmentedFont {
     characters: HashMap<char, SegmentBits>,
+    /// Glyph shown for characters missing from the font
+    fallback: Option<SegmentBits>,
+    /// Font providing the glyphs missing from this one
+    base: Option<Arc<SegmentedFont>>,
 }
 
 impl SegmentedFont {
     pub const fn new(characters: HashMap<char, SegmentBits>) -> Self {
-        Self { characters }
+        Self {
+            characters,
+            fallback: None,
+            base: None,
+        }
     }
 
+    /// Layers this font over `base`, so characters missing from this font
+    /// are looked up in `base` and its own fallback fonts. This allows
+    /// overriding a few glyphs without copying a whole font.
+    pub fn with_fallback(self, base: Arc<SegmentedFont>) -> Self {
+        Self {
+            base: Some(base),
+            ..self
+        }
+    }
+
+    /// Replaces the font providing the glyphs missing from this one, as set
+    /// by [`Self::with_fallback`].
+    pub fn set_fallback_font(&mut self, base: Option<Arc<SegmentedFont>>) {
+        self.base = base;
+    }
+
+    /// Glyph of `ch` in this font or its fallback fonts.
     pub fn get(&self, ch: &char) -> Option<&SegmentBits> {
-        self.characters.get(ch)
+        self.characters
+            .get(ch)
+            .or_else(|| self.base.as_ref()?.get(ch))
+    }
+
+    /// Like [`Self::get`], but a lowercase character missing from the font
+    /// falls back to its uppercase form.
+    pub fn get_with_fallback(&self, ch: &char) -> Option<&SegmentBits> {
+        self.get(ch).or_else(|| {
+            if !ch.is_lowercase() {
+                return None;
+            }
+            let mut upper = ch.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(upper), None) => self.get(&upper),
+                _ => None,
+            }
+        })
+    }
+
+    /// Segments to display for `ch`. Characters missing from the font are
+    /// shown as the fallback glyph, or left blank if there is none.
+    pub fn glyph(&self, ch: &char) -> SegmentBits {
+        self.get_with_fallback(ch)
+            .copied()
+            .or(self.fallback)
+            .unwrap_or_default()
+    }
+
+    pub fn fallback_glyph(&self) -> Option<SegmentBits> {
+        self.fallback
+    }
+
+    pub fn set_fallback_glyph(&mut self, fallback: Option<SegmentBits>) {
+        self.fallback = fallback;
+    }
+
+    /// Finds the character displayed as `segments`. If several characters
+    /// share the same segments, the lowest one is returned.
+    ///
+    /// This is a linear scan over all characters of the font.
+    pub fn char_for(&self, segments: SegmentBits) -> Option<char> {
+        self.iter()
+            .filter(|&(_, bits)| bits == segments)
+            .map(|(ch, _)| ch)
+            .min()
+    }
+
+    /// Sets the segments of a character, returning the previous ones.
+    pub fn insert(
+        &mut self,
+        ch: char,
+        segments: SegmentBits,
+    ) -> Option<SegmentBits> {
+        self.characters.insert(ch, segments)
+    }
+
+    /// Removes the glyph of a character, returning its segments. The glyph
+    /// of a fallback font is shown in its place.
+    pub fn remove(&mut self, ch: &char) -> Option<SegmentBits> {
+        self.characters.remove(ch)
+    }
+
+    /// Characters of `chars` which have no glyph in this font, in the given
+    /// order.
+    pub fn missing(&self, chars: impl IntoIterator<Item = char>) -> Vec<char> {
+        chars
+            .into_iter()
+            .filter(|ch| self.get(ch).is_none())
+            .collect()
+    }
+
+    /// Printable ASCII characters which have no glyph in this font.
+    pub fn missing_ascii(&self) -> Vec<char> {
+        self.missing(' '..='~')
+    }
+
+    /// Iterates over all characters and their segments in arbitrary order,
+    /// including the characters of the fallback fonts not overridden by
+    /// this one.
+    pub fn iter(&self) -> impl Iterator<Item = (char, SegmentBits)> + '_ {
+        let base: Box<dyn Iterator<Item = _>> = match &self.base {
+            Some(base) => Box::new(
+                base.iter()
+                    .filter(|(ch, _)| !self.characters.contains_key(ch)),
+            ),
+            None => Box::new(std::iter::empty()),
+        };
+        self.characters
+            .iter()
+            .map(|(&ch, &bits)| (ch, bits))
+            .chain(base)
+    }
+
+    /// Reads a font from a JSON object mapping each character to the names
+    /// of its segments, like `{ "A": ["A1", "A2", "B"] }`. The glyph for
+    /// missing characters is given by the key [`JSON_FALLBACK_KEY`].
+    pub fn from_json(reader: impl io::Read) -> Result<Self, FontError> {
+        let mut table: HashMap<String, Vec<String>> =
+            serde_json::from_reader(reader).map_err(FontError::Json)?;
+
+        let fallback = table
+            .remove(JSON_FALLBACK_KEY)
+            .map(|names| parse_names(&names))
+            .transpose()?;
+        let characters = table
+            .into_iter()
+            .map(|(key, names)| {
+                let mut chars = key.chars();
+                let (Some(ch), None) = (chars.next(), chars.next()) else {
+                    return Err(FontError::InvalidCharacter(key));
+                };
+                Ok((ch, parse_names(&names)?))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            fallback,
+            ..Self::new(characters)
+        })
+    }
+
+    /// Reads the glyph table of a C header like the firmware's
+    /// `char_16seg_font.h`. The first array initializer in `source` is the
+    /// table, and its first entry is the glyph of `first`.
+    ///
+    /// Entries are combined with `|` from numbers in the bit layout of
+    /// [`SegmentBits`], shifts like `(1 << 3)`, names defined with `#define`
+    /// and segment flags whose name ends in a segment name, like `SEG_A1`.
+    pub fn from_c_header(source: &str, first: char) -> Result<Self, FontError> {
+        let source = strip_comments(source);
+        let mut defines = HashMap::new();
+        let mut body = String::new();
+        for line in source.lines() {
+            let Some(define) = line.trim().strip_prefix("#define") else {
+                if !line.trim_start().starts_with('#') {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+                continue;
+            };
+            let mut parts = define.trim().splitn(2, char::is_whitespace);
+            if let (Some(name), Some(expr)) = (parts.next(), parts.next()) {
+                // Defines unrelated to segments are skipped
+                if let Ok(bits) = eval_flags(expr, &defines) {
+                    defines.insert(name.to_string(), bits);
+                }
+            }
+        }
+
+        let table = body
+            .find('{')
+            .and_then(|start| {
+                let end = start + body[start..].find('}')?;
+                Some(&body[start + 1..end])
+            })
+            .ok_or_else(|| FontError::Header("no glyph table found".into()))?;
+
+        let mut entries: Vec<_> = table.split(',').map(str::trim).collect();
+        if entries.last() == Some(&"") {
+            entries.pop();
+        }
+        let characters = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let ch = char::from_u32(first as u32 + index as u32)
+                    .ok_or_else(|| {
+                        FontError::Header(format!("glyph {index} out of range"))
+                    })?;
+                Ok((ch, eval_flags(entry, &defines)?))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(characters))
+    }
+
+    /// Reads every `.json` font in `dir`, named after its file stem and
+    /// sorted by name. C headers ending in `.h` are read as well, with their
+    /// table starting at [`HEADER_FIRST_CHAR`].
+    pub fn load_dir(
+        dir: impl AsRef<Path>,
+    ) -> io::Result<Vec<(String, Result<Self, FontError>)>> {
+        let mut fonts = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(ext) = path.extension() else {
+                continue;
+            };
+            let Some(name) = path.file_stem() else {
+                continue;
+            };
+            let font = if ext == "json" {
+                fs::File::open(&path)
+                    .map_err(FontError::Io)
+                    .and_then(|file| Self::from_json(io::BufReader::new(file)))
+            } else if ext == "h" {
+                fs::read_to_string(&path).map_err(FontError::Io).and_then(
+                    |src| Self::from_c_header(&src, HEADER_FIRST_CHAR),
+                )
+            } else {
+                continue;
+            };
+            fonts.push((name.to_string_lossy().into_owned(), font));
+        }
+        fonts.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(fonts)
+    }
+
+    /// Writes the font in the format read by [`Self::from_json`], sorted by
+    /// character. Only the glyphs of this font and its fallback glyph are
+    /// written, not the glyphs of its fallback fonts.
+    pub fn to_json(&self, writer: impl io::Write) -> serde_json::Result<()> {
+        let names = |bits: SegmentBits| -> Vec<&str> {
+            bits.iter().map(|s| s.name()).collect()
+        };
+        let mut table: BTreeMap<String, Vec<&str>> = self
+            .characters
+            .iter()
+            .map(|(ch, &bits)| (ch.to_string(), names(bits)))
+            .collect();
+        if let Some(fallback) = self.fallback {
+            table.insert(JSON_FALLBACK_KEY.into(), names(fallback));
+        }
+        serde_json::to_writer_pretty(writer, &table)
+    }
+}
+
+/// Key of the fallback glyph in fonts read by [`SegmentedFont::from_json`].
+/// It is longer than one character, so it never clashes with a glyph.
+pub const JSON_FALLBACK_KEY: &str = "fallback";
+
+/// Combines segments given by their names.
+fn parse_names(names: &[String]) -> Result<SegmentBits, FontError> {
+    names.iter().try_fold(SegmentBits::new(), |bits, name| {
+        name.parse::<Segment>()
+            .map(|segment| bits | segment)
+            .map_err(FontError::Segment)
+    })
+}
+
+/// Character of the first glyph in C headers read by
+/// [`SegmentedFont::load_dir`].
+pub const HEADER_FIRST_CHAR: char = ' ';
+
+/// Replaces `//` and `/* */` comments in C source by whitespace.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+            out.push(' ');
+        } else {
+            let ch = rest.chars().next().unwrap();
+            out.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
     }
+    out
+}
+
+/// Evaluates an expression of segment flags combined with `|`.
+fn eval_flags(
+    expr: &str,
+    defines: &HashMap<String, SegmentBits>,
+) -> Result<SegmentBits, FontError> {
+    let expr: String = expr.chars().filter(|&c| c != '(' && c != ')').collect();
+    expr.split('|').try_fold(SegmentBits::new(), |bits, term| {
+        let term = term.trim();
+        let flag = if let Some((value, shift)) = term.split_once("<<") {
+            match (parse_int(value.trim()), parse_int(shift.trim())) {
+                (Some(value), Some(shift)) => {
+                    SegmentBits::from(value.checked_shl(shift).unwrap_or(0))
+                }
+                _ => {
+                    return Err(FontError::Header(format!(
+                        "invalid shift `{term}`"
+                    )))
+                }
+            }
+        } else if let Some(value) = parse_int(term) {
+            SegmentBits::from(value)
+        } else if let Some(&define) = defines.get(term) {
+            define
+        } else {
+            let name = term.rsplit('_').next().unwrap_or(term);
+            SegmentBits::new()
+                | name.parse::<Segment>().map_err(FontError::Segment)?
+        };
+        Ok(bits | flag)
+    })
 }
 
+/// Parses a C integer literal, ignoring type suffixes.
+fn parse_int(literal: &str) -> Option<u32> {
+    let literal = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = literal
+        .strip_prefix("0b")
+        .or_else(|| literal.strip_prefix("0B"))
+    {
+        u32::from_str_radix(bin, 2).ok()
+    } else {
+        literal.parse().ok()
+    }
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// A key of the table is not exactly one character.
+    InvalidCharacter(String),
+    Segment(ParseSegmentError),
+    /// The C header could not be read as a glyph table.
+    Header(String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Json(err) => write!(f, "invalid font file: {err}"),
+            Self::InvalidCharacter(key) => {
+                write!(f, "`{key}` is not a single character")
+            }
+            Self::Segment(err) => err.fmt(f),
+            Self::Header(msg) => write!(f, "invalid C header: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
 #[macro_export]
 macro_rules! segmented_font {
     [$($char:literal => $($bits:tt),+);* $(;)?] => {
@@ -122,3 +490,149 @@ pub static DEFAULT: LazyLock<SegmentedFont> = LazyLock::new(|| {
         'z' => G1, K, D1;
     ]
 });
+
+/// Hexadecimal digits for seven-segment displays, which only use `A1`, `B`,
+/// `C`, `D1`, `E`, `F`, `G1` and `DP`.
+pub static SEVEN_SEGMENT: LazyLock<SegmentedFont> = LazyLock::new(|| {
+    segmented_font![
+        ' ' => 0;
+        '-' => G1;
+        '.' => DP;
+        '0' => A1, B, C, D1, E, F;
+        '1' => B, C;
+        '2' => A1, B, G1, E, D1;
+        '3' => A1, B, G1, C, D1;
+        '4' => F, G1, B, C;
+        '5' => A1, F, G1, C, D1;
+        '6' => A1, F, G1, E, C, D1;
+        '7' => A1, B, C;
+        '8' => A1, B, C, D1, E, F, G1;
+        '9' => A1, B, C, D1, F, G1;
+        'A' => A1, B, C, E, F, G1;
+        'B' => F, E, D1, C, G1;
+        'C' => A1, F, E, D1;
+        'D' => B, C, D1, E, G1;
+        'E' => A1, F, G1, E, D1;
+        'F' => A1, F, G1, E;
+    ]
+});
+
+/// [`DEFAULT`] with the split top and bottom bars merged for fourteen-segment
+/// displays.
+pub static FOURTEEN_SEGMENT: LazyLock<SegmentedFont> = LazyLock::new(|| {
+    SegmentedFont::new(
+        DEFAULT
+            .iter()
+            .map(|(ch, bits)| {
+                (ch, DisplayKind::FourteenSegment.normalize(bits))
+            })
+            .collect(),
+    )
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_keeps_own_glyphs_and_fallback() {
+        let base = Arc::new(segmented_font!['A' => A1; 'B' => B]);
+        let mut font = segmented_font!['A' => A2, G1].with_fallback(base);
+        font.set_fallback_glyph(Some(SegmentBits::all()));
+
+        let mut json = Vec::new();
+        font.to_json(&mut json).unwrap();
+        let read = SegmentedFont::from_json(json.as_slice()).unwrap();
+
+        let mut expected = segmented_font!['A' => A2, G1];
+        expected.set_fallback_glyph(Some(SegmentBits::all()));
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn json_round_trips_default_font() {
+        let mut json = Vec::new();
+        DEFAULT.to_json(&mut json).unwrap();
+        let read = SegmentedFont::from_json(json.as_slice()).unwrap();
+        assert_eq!(read, *DEFAULT);
+    }
+
+    #[test]
+    fn char_for_finds_lowest_match() {
+        assert_eq!(DEFAULT.char_for(DEFAULT.glyph(&'A')), Some('A'));
+        // 'k' looks the same as 'K'
+        assert_eq!(DEFAULT.char_for(DEFAULT.glyph(&'k')), Some('K'));
+        assert_eq!(DEFAULT.char_for(SegmentBits::all()), None);
+    }
+
+    #[test]
+    fn override_falls_back_to_base() {
+        let base = Arc::new(DEFAULT.clone());
+        let font = segmented_font!['A' => A1].with_fallback(base);
+        assert_eq!(font.glyph(&'A'), SegmentBits::new() | Segment::A1);
+        assert_eq!(font.glyph(&'B'), DEFAULT.glyph(&'B'));
+        assert_eq!(font.get(&'\u{2603}'), None);
+    }
+
+    #[test]
+    fn reads_c_header() {
+        let source = "
+            #include <stdint.h>
+            #define SEG_A1 (1 << 0)
+            #define SEG_B 0x4
+            #define TOP (SEG_A1 | SEG_A2)
+            /* Glyphs from ' ' on */
+            const uint32_t font[] = {
+                0, // space
+                SEG_B | SEG_C,
+                TOP | (1 << 16),
+            };
+        ";
+        let font = SegmentedFont::from_c_header(source, ' ').unwrap();
+        assert_eq!(font.get(&' '), Some(&SegmentBits::new()));
+        assert_eq!(font.get(&'!'), Some(&(Segment::B | Segment::C)));
+        assert_eq!(
+            font.get(&'"'),
+            Some(&(Segment::A1 | Segment::A2 | Segment::DP))
+        );
+        assert_eq!(font.get(&'#'), None);
+    }
+
+    #[test]
+    fn rejects_malformed_c_header() {
+        let unknown = "const uint32_t font[] = { SEG_A1, SEG_X };";
+        assert!(matches!(
+            SegmentedFont::from_c_header(unknown, ' '),
+            Err(FontError::Segment(_))
+        ));
+        let shift = "const uint32_t font[] = { 1 << x };";
+        assert!(matches!(
+            SegmentedFont::from_c_header(shift, ' '),
+            Err(FontError::Header(_))
+        ));
+        assert!(matches!(
+            SegmentedFont::from_c_header("#define SEG_A1 1", ' '),
+            Err(FontError::Header(_))
+        ));
+    }
+
+    #[test]
+    fn default_font_covers_alphanumerics() {
+        let alphanumerics = ('0'..='9').chain('A'..='Z').chain('a'..='z');
+        assert_eq!(DEFAULT.missing(alphanumerics), []);
+    }
+
+    #[test]
+    fn fourteen_segment_glyphs() {
+        use Segment::*;
+
+        let font = &*FOURTEEN_SEGMENT;
+        assert_eq!(font.glyph(&'A'), A1 | B | C | E | F | G1 | G2);
+        assert_eq!(font.glyph(&'0'), A1 | B | C | D1 | E | F | J | K);
+        assert_eq!(font.glyph(&'T'), A1 | I | L);
+        let available = DisplayKind::FourteenSegment.segments();
+        for (ch, bits) in font.iter() {
+            assert_eq!(bits & available, bits, "{ch:?} uses missing segments");
+        }
+    }
+}
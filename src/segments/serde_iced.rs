@@ -0,0 +1,166 @@
+//! Serialization of `iced` types used in [`super::DigitOptions`], to be used
+//! with `#[serde(with = "...")]`. Colors are stored as `[r, g, b, a]`.
+
+use iced::{
+    widget::canvas::{gradient, Gradient, Style},
+    Color, Point,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub mod color {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        color: &Color,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b, color.a].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Color, D::Error> {
+        <[f32; 4]>::deserialize(deserializer)
+            .map(|[r, g, b, a]| Color::from_rgba(r, g, b, a))
+    }
+}
+
+pub mod size {
+    use iced::Size;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        size: &Size,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        [size.width, size.height].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Size, D::Error> {
+        <[f32; 2]>::deserialize(deserializer)
+            .map(|[width, height]| Size::new(width, height))
+    }
+}
+
+/// Built-in themes, stored by their name.
+pub mod theme {
+    use iced::Theme;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        theme: &Theme,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        theme.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Theme, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Theme::ALL
+            .iter()
+            .find(|theme| theme.to_string() == name)
+            .cloned()
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("unknown theme `{name}`"))
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StyleDef {
+    Solid(#[serde(with = "color")] Color),
+    LinearGradient {
+        start: [f32; 2],
+        end: [f32; 2],
+        stops: Vec<StopDef>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct StopDef {
+    offset: f32,
+    #[serde(with = "color")]
+    color: Color,
+}
+
+impl From<&Style> for StyleDef {
+    fn from(style: &Style) -> Self {
+        match style {
+            Style::Solid(color) => Self::Solid(*color),
+            Style::Gradient(Gradient::Linear(linear)) => Self::LinearGradient {
+                start: [linear.start.x, linear.start.y],
+                end: [linear.end.x, linear.end.y],
+                stops: linear
+                    .stops
+                    .iter()
+                    .flatten()
+                    .map(|stop| StopDef {
+                        offset: stop.offset,
+                        color: stop.color,
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<StyleDef> for Style {
+    fn from(style: StyleDef) -> Self {
+        match style {
+            StyleDef::Solid(color) => Self::Solid(color),
+            StyleDef::LinearGradient { start, end, stops } => {
+                let linear = gradient::Linear::new(
+                    Point::new(start[0], start[1]),
+                    Point::new(end[0], end[1]),
+                );
+                Self::Gradient(Gradient::Linear(
+                    stops.into_iter().fold(linear, |linear, stop| {
+                        linear.add_stop(stop.offset, stop.color)
+                    }),
+                ))
+            }
+        }
+    }
+}
+
+pub mod style {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        style: &Style,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        StyleDef::from(style).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Style, D::Error> {
+        StyleDef::deserialize(deserializer).map(Style::from)
+    }
+}
+
+pub mod option_style {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        style: &Option<Style>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        style.as_ref().map(StyleDef::from).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Style>, D::Error> {
+        Option::<StyleDef>::deserialize(deserializer)
+            .map(|style| style.map(Style::from))
+    }
+}
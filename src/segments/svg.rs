@@ -0,0 +1,212 @@
+//! Export of a board of digits as a standalone SVG document.
+
+use std::io::{self, Write};
+
+use iced::{widget::canvas::Style, Color};
+
+use super::{
+    geometry::{self, SegmentShape},
+    DigitOptions, SegmentBits,
+};
+
+/// Arrangement of the digits on the exported board.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgLayout {
+    pub char_spacing: f32,
+    pub line_spacing: f32,
+    pub padding: f32,
+    pub background: Color,
+}
+
+/// Digit on an exported board.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoardCell {
+    pub segments: SegmentBits,
+    /// Solid color of the lit segments instead of the shared style, as set
+    /// by the markup or for the row
+    pub color: Option<Color>,
+}
+
+impl From<SegmentBits> for BoardCell {
+    fn from(segments: SegmentBits) -> Self {
+        Self {
+            segments,
+            color: None,
+        }
+    }
+}
+
+/// Writes one `<path>` per drawn segment of every digit of the `board`,
+/// which is given as a list of rows.
+pub fn write_svg(
+    out: &mut impl Write,
+    board: &[Vec<BoardCell>],
+    options: &DigitOptions,
+    layout: &SvgLayout,
+) -> io::Result<()> {
+    let size = options.size;
+    let cols = board.iter().map(Vec::len).max().unwrap_or(0);
+    let rows = board.len();
+    let width = 2. * layout.padding
+        + cols as f32 * size.width
+        + cols.saturating_sub(1) as f32 * layout.char_spacing;
+    let height = 2. * layout.padding
+        + rows as f32 * size.height
+        + rows.saturating_sub(1) as f32 * layout.line_spacing;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )?;
+
+    let on_fill = options.lit_fill();
+    writeln!(out, "<defs>")?;
+    write_gradient(out, "on", &on_fill)?;
+    if let Some(off_fill) = &options.off_fill {
+        write_gradient(out, "off", off_fill)?;
+    }
+    writeln!(out, "</defs>")?;
+
+    writeln!(
+        out,
+        r#"<rect width="{width}" height="{height}" {}/>"#,
+        paint_attribute("fill", "bg", &Style::Solid(layout.background), 1.)
+    )?;
+
+    let drawing_options = options.drawing_options();
+    for (row, digits) in board.iter().enumerate() {
+        let row = options.board_row(row, rows);
+        for (col, cell) in digits.iter().enumerate() {
+            let col = options.board_column(col, cols);
+            let cell_fill =
+                cell.color.map(|color| options.dimmed(&Style::Solid(color)));
+            let x = layout.padding
+                + col as f32 * (size.width + layout.char_spacing)
+                + size.width * 0.5;
+            let y = layout.padding
+                + row as f32 * (size.height + layout.line_spacing)
+                + size.height * 0.5;
+            writeln!(out, r#"<g transform="translate({x} {y})">"#)?;
+
+            for segment in SegmentBits::all().iter() {
+                let lit = cell.segments.contains(segment);
+                let fill = match (lit, &options.off_fill) {
+                    (true, _) => paint_attributes(
+                        "on",
+                        cell_fill.as_ref().unwrap_or(&on_fill),
+                        options,
+                    ),
+                    (false, Some(off_fill)) => {
+                        paint_attributes("off", off_fill, options)
+                    }
+                    (false, None) => continue,
+                };
+
+                match options.kind.shape(segment) {
+                    Some(SegmentShape::Outline(instructions)) => {
+                        let corners = geometry::round_corners(
+                            &instructions.outline(&drawing_options),
+                            options.corner_radius,
+                        );
+                        write!(out, r#"<path d=""#)?;
+                        for (i, corner) in corners.iter().enumerate() {
+                            let cmd = if i == 0 { 'M' } else { 'L' };
+                            let start = corner.start;
+                            write!(out, "{cmd}{} {} ", start.x, start.y)?;
+                            if !corner.is_sharp() {
+                                let (control, end) =
+                                    (corner.control, corner.end);
+                                write!(
+                                    out,
+                                    "Q{} {} {} {} ",
+                                    control.x, control.y, end.x, end.y
+                                )?;
+                            }
+                        }
+                        writeln!(out, r#"Z" {fill}/>"#)?;
+                    }
+                    Some(SegmentShape::Dot) => {
+                        let center = geometry::project_point(
+                            &geometry::DP,
+                            &drawing_options,
+                        );
+                        let radius = geometry::DP_RADIUS * options.thickness;
+                        writeln!(
+                            out,
+                            r#"<circle cx="{}" cy="{}" r="{radius}" {fill}/>"#,
+                            center.x, center.y
+                        )?;
+                    }
+                    None => (),
+                }
+            }
+
+            writeln!(out, "</g>")?;
+        }
+    }
+
+    writeln!(out, "</svg>")
+}
+
+fn write_gradient(
+    out: &mut impl Write,
+    id: &str,
+    style: &Style,
+) -> io::Result<()> {
+    let Style::Gradient(iced::widget::canvas::Gradient::Linear(linear)) = style
+    else {
+        return Ok(());
+    };
+
+    writeln!(
+        out,
+        r#"<linearGradient id="{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+        linear.start.x, linear.start.y, linear.end.x, linear.end.y
+    )?;
+    for stop in linear.stops.iter().flatten() {
+        writeln!(
+            out,
+            r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+            stop.offset,
+            hex(stop.color),
+            stop.color.a
+        )?;
+    }
+    writeln!(out, "</linearGradient>")
+}
+
+/// Fill and stroke of a segment drawn with `style`, as set by the render
+/// mode. Gradients are referenced by their `id`.
+fn paint_attributes(id: &str, style: &Style, options: &DigitOptions) -> String {
+    let fill = match options.fill_alpha() {
+        Some(alpha) => paint_attribute("fill", id, style, alpha),
+        None => r#"fill="none""#.to_string(),
+    };
+    match options.stroke_width() {
+        Some(width) => format!(
+            r#"{fill} {} stroke-width="{width}""#,
+            paint_attribute("stroke", id, style, 1.)
+        ),
+        None => fill,
+    }
+}
+
+/// Sets the `fill` or `stroke` attribute `name` to `style`, with its
+/// opacity multiplied by `alpha`.
+fn paint_attribute(name: &str, id: &str, style: &Style, alpha: f32) -> String {
+    match style {
+        Style::Solid(color) => format!(
+            r#"{name}="{}" {name}-opacity="{}""#,
+            hex(*color),
+            color.a * alpha
+        ),
+        Style::Gradient(_) => {
+            format!(r#"{name}="url(#{id})" {name}-opacity="{alpha}""#)
+        }
+    }
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
@@ -0,0 +1,53 @@
+//! Streams the display to hardware running the
+//! [Cheetah firmware](https://github.com/CatoLynx/Cheetah_Firmware).
+
+use std::{io, time::Duration};
+
+use crate::segments::{SegmentBits, SegmentOrder, HARDWARE_BYTES};
+
+pub const BAUD_RATE: u32 = 115_200;
+/// Number of bytes each cell occupies in a frame.
+pub const BYTES_PER_CELL: usize = HARDWARE_BYTES;
+
+/// Encodes cells into the bit layout of the 16-segment SPI driver.
+///
+/// Every cell is sent as a 24-bit big-endian word wired as
+/// [`SegmentOrder::CHEETAH`], i.e. `A1` is bit 0 and `DP` is bit 16. This
+/// matches the glyph table of `char_16seg_font.h`. Cells are sent in the
+/// given order, row by row for a whole board.
+pub fn encode_frame(cells: &[SegmentBits]) -> Vec<u8> {
+    cells
+        .iter()
+        .flat_map(|bits| bits.to_hardware_bytes(&SegmentOrder::CHEETAH))
+        .collect()
+}
+
+pub fn open(path: &str) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+    serialport::new(path, BAUD_RATE)
+        .timeout(Duration::from_millis(100))
+        .open()
+}
+
+pub fn write_frame(
+    port: &mut dyn serialport::SerialPort,
+    frame: &[u8],
+) -> io::Result<()> {
+    port.write_all(frame)?;
+    port.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segments::segmented_font::DEFAULT;
+
+    #[test]
+    fn encodes_default_glyphs() {
+        let cells: Vec<_> =
+            "A1. ".chars().map(|ch| DEFAULT.glyph(&ch)).collect();
+        assert_eq!(
+            encode_frame(&cells),
+            [0x00, 0x03, 0xCF, 0x00, 0x10, 0x0C, 0x01, 0x00, 0x00, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Shows the lines piped into standard input, like `tail -f log |
+//! cato-display-app --stdin`.
+//!
+//! Every line is handled like a line received over TCP by [`crate::remote`].
+
+use std::io::BufRead;
+
+use iced::futures::{executor, SinkExt};
+
+use crate::app::Message;
+
+/// Reads standard input on a background thread until it is closed.
+pub fn read_lines() -> iced::Subscription<Message> {
+    struct Stdin;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<Stdin>(),
+        64,
+        |mut output| async move {
+            // Reading stdin blocks, which would stall the async runtime
+            std::thread::spawn(move || {
+                for line in std::io::stdin().lock().lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(err) => {
+                            eprintln!("Failed to read from stdin: {err}");
+                            break;
+                        }
+                    };
+                    let sent = executor::block_on(
+                        output.send(Message::RemoteText(line)),
+                    );
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            });
+            std::future::pending().await
+        },
+    )
+}
@@ -0,0 +1,100 @@
+//! Countdown and stopwatch shown on the display as `MM:SS`.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimerMode {
+    /// Counts down from the set duration to zero
+    #[default]
+    Countdown,
+    /// Counts up from zero
+    Stopwatch,
+}
+
+impl TimerMode {
+    pub const ALL: [Self; 2] = [Self::Countdown, Self::Stopwatch];
+}
+
+impl fmt::Display for TimerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Countdown => "Countdown",
+            Self::Stopwatch => "Stopwatch",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub mode: TimerMode,
+    /// Time the countdown starts from
+    pub duration: Duration,
+    /// Time counted before the timer was last started
+    elapsed: Duration,
+    /// When the timer was started, if it is running
+    started: Option<Instant>,
+}
+
+impl Timer {
+    pub const fn new(mode: TimerMode, duration: Duration) -> Self {
+        Self {
+            mode,
+            duration,
+            elapsed: Duration::ZERO,
+            started: None,
+        }
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn start(&mut self, now: Instant) {
+        self.started.get_or_insert(now);
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        self.elapsed = self.elapsed(now);
+        self.started = None;
+    }
+
+    /// Stops the timer and sets it back to its initial time.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.started = None;
+    }
+
+    /// Time counted so far, which is never more than the duration of a
+    /// countdown.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        let running = self
+            .started
+            .map_or(Duration::ZERO, |started| now.duration_since(started));
+        let elapsed = self.elapsed + running;
+        match self.mode {
+            TimerMode::Countdown => elapsed.min(self.duration),
+            TimerMode::Stopwatch => elapsed,
+        }
+    }
+
+    /// Whether a countdown has reached zero.
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.mode == TimerMode::Countdown && self.elapsed(now) >= self.duration
+    }
+
+    /// Shown time as `MM:SS`. A countdown rounds up, so that it only shows
+    /// `00:00` once it has finished.
+    pub fn text(&self, now: Instant) -> String {
+        let elapsed = self.elapsed(now);
+        let secs = match self.mode {
+            TimerMode::Countdown => {
+                (self.duration - elapsed).as_secs_f64().ceil() as u64
+            }
+            TimerMode::Stopwatch => elapsed.as_secs(),
+        };
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
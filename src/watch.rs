@@ -0,0 +1,92 @@
+//! Shows the contents of a text file, reloading it whenever it changes.
+//!
+//! This lets an external process drive the display by writing a file.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use iced::futures::{channel::mpsc::Sender, executor, SinkExt};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::app::Message;
+
+/// Time without changes before the file is read again, so that a file
+/// written in several steps is only loaded once
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Sends the contents of the file at `path` once and after every change.
+pub fn watch(path: PathBuf) -> iced::Subscription<Message> {
+    struct FileWatcher;
+
+    iced::subscription::channel(
+        (std::any::TypeId::of::<FileWatcher>(), path.clone()),
+        4,
+        move |mut output| async move {
+            // Waiting for file events blocks, which would stall the async
+            // runtime
+            std::thread::spawn(move || {
+                if let Err(err) = watch_blocking(&path, &mut output) {
+                    let error = format!("Failed to watch the file: {err}");
+                    let _ = executor::block_on(
+                        output.send(Message::WatchedFile(Err(error))),
+                    );
+                }
+            });
+            std::future::pending().await
+        },
+    )
+}
+
+fn watch_blocking(
+    path: &Path,
+    output: &mut Sender<Message>,
+) -> notify::Result<()> {
+    let (events, changes) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(events)?;
+    // Watching the directory keeps working while the file is deleted and
+    // written again, which is how many programs save files
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    loop {
+        let contents = read(path);
+        if executor::block_on(output.send(Message::WatchedFile(contents)))
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        // Wait for a change to the file, then for the writes to settle
+        loop {
+            let Ok(event) = changes.recv() else {
+                return Ok(());
+            };
+            let event = event?;
+            // Reading the file causes access events
+            if !matches!(event.kind, EventKind::Access(_))
+                && event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name() == path.file_name())
+            {
+                break;
+            }
+        }
+        while changes.recv_timeout(DEBOUNCE).is_ok() {}
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => {
+            "The file is missing, waiting for it to be created".to_string()
+        }
+        _ => format!("Failed to read the file: {err}"),
+    })
+}